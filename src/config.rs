@@ -0,0 +1,165 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// `key = value` options kept between runs in the XDG config directory
+#[derive(Default)]
+pub struct Config {
+    values: Vec<(String, String)>,
+}
+impl Config {
+    /// Reads the config file and returns it, the config is empty if there is no file
+    pub fn load() -> Config {
+        match path().map(fs::read_to_string) {
+            Some(Ok(text)) => Config::parse(&text),
+            _ => Config::default(),
+        }
+    }
+    /// Returns the config of the `key = value` lines of text, skipping blank lines
+    /// and `#` comments, a value may contain `=`
+    fn parse(text: &str) -> Config {
+        let mut config = Config::default();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                config.set(key.trim(), value.trim());
+            }
+        }
+        config
+    }
+    /// Writes the config file creating its directory if needed
+    pub fn save(&self) -> io::Result<()> {
+        let path = path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no HOME or XDG_CONFIG_HOME"))?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, self.to_text())
+    }
+    /// Returns the text of the config file, one `key = value` line for each key
+    fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (key, value) in self.values.iter() {
+            text.push_str(&format!("{} = {}\n", key, value));
+        }
+        text
+    }
+    /// Returns the value of key if it is there
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value.as_str())
+    }
+    /// Sets the value of key replacing the old one
+    pub fn set(&mut self, key: &str, value: &str) {
+        match self.values.iter_mut().find(|(k, _)| k == key) {
+            Some((_, old)) => *old = value.to_string(),
+            None => self.values.push((key.to_string(), value.to_string())),
+        }
+    }
+}
+
+/// Most lines kept in the history file
+const HISTORY_LINES: usize = 100;
+
+/// Returns the lines of the history file, the oldest first, or nothing if there is no file
+pub fn load_history() -> Vec<String> {
+    history_path().map_or_else(Vec::new, |path| read_lines(&path))
+}
+/// Adds line to the end of the history file keeping only its last HISTORY_LINES lines
+pub fn add_history(line: &str) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no HOME or XDG_CONFIG_HOME"))?;
+    append_line(&path, line, HISTORY_LINES)
+}
+/// Returns the lines of the file at path or nothing if it cannot be read
+fn read_lines(path: &Path) -> Vec<String> {
+    match fs::read_to_string(path) {
+        Ok(text) => text.lines().map(str::to_string).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+/// Adds line to the end of the file at path keeping only its last most lines,
+/// the file and its directory are created if needed
+fn append_line(path: &Path, line: &str, most: usize) -> io::Result<()> {
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let mut lines = read_lines(path);
+    lines.push(line.to_string());
+    let start = lines.len().saturating_sub(most);
+    let mut text = String::new();
+    for line in lines[start..].iter() {
+        text.push_str(line);
+        text.push('\n');
+    }
+    fs::write(path, text)
+}
+
+/// Returns the directory of the game files in the XDG config directory
+pub fn directory() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(base.join("tic_tac_toe"))
+}
+/// Returns the path of the config file
+fn path() -> Option<PathBuf> {
+    directory().map(|directory| directory.join("config"))
+}
+/// Returns the path of the file of the player results against the adaptive computer
+fn history_path() -> Option<PathBuf> {
+    directory().map(|directory| directory.join("history"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    #[test]
+    fn config_text_round_trip() {
+        let mut config =
+            Config::parse("# saved settings\n\n mode = computer \nname_x = a=b\nbroken\n");
+        assert_eq!(config.get("mode"), Some("computer"));
+        assert_eq!(config.get("name_x"), Some("a=b"));
+        assert_eq!(config.get("broken"), None);
+        assert_eq!(config.get("# saved settings"), None);
+        config.set("mode", "person");
+        config.set("theme", "ocean");
+        let text = config.to_text();
+        assert_eq!(text, "mode = person\nname_x = a=b\ntheme = ocean\n");
+        let config = Config::parse(&text);
+        assert_eq!(config.get("mode"), Some("person"));
+        assert_eq!(config.get("name_x"), Some("a=b"));
+        assert_eq!(config.get("theme"), Some("ocean"));
+    }
+
+    #[test]
+    fn history_keeps_its_last_lines() {
+        let directory = env::temp_dir().join(format!("tic_tac_toe_test_{}", process::id()));
+        let path = directory.join("history");
+        assert!(read_lines(&path).is_empty());
+        for line in ["win 6 50 0.1", "draw 6 50 0.2", "loss 6 50 0.3"].iter() {
+            append_line(&path, line, 2).unwrap();
+        }
+        assert_eq!(read_lines(&path), vec!["draw 6 50 0.2", "loss 6 50 0.3"]);
+        append_line(&path, "win smart 40 0", HISTORY_LINES).unwrap();
+        assert_eq!(read_lines(&path).len(), 3);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
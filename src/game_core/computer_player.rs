@@ -6,293 +6,477 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at http://mozilla.org/MPL/2.0/.
 */
 
-use crate::game_core::{rand, Cell, Controller, Level};
+use crate::game_core::rand::Rng;
+use crate::game_core::{
+    Cell, Controller, Difficulty, GameStatus, Level, Move, Outcome, Player, Position, Search,
+};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 
+/// Difficulty of each value of the slider from 1 to Difficulty::MAX_SLIDER
+const SLIDER: [Difficulty; 10] = [
+    Difficulty::new(Some(0), 1.0, false),
+    Difficulty::new(Some(1), 0.4, false),
+    Difficulty::new(Some(1), 0.3, true),
+    Difficulty::new(Some(1), 0.0, true),
+    Difficulty::new(Some(2), 0.15, true),
+    Difficulty::new(Some(3), 0.1, true),
+    Difficulty::new(Some(4), 0.1, true),
+    Difficulty::new(Some(4), 0.03, true),
+    Difficulty::new(Some(6), 0.01, true),
+    Difficulty::new(None, 0.0, true),
+];
+
+/// Most positions the search of a move goes through before pruning, the depth
+/// is lowered on big playgrounds to keep each move within about a second
+const SEARCH_NODES: u64 = 10_000_000;
+/// Number of the last results of the opponent that adaptive mode follows
+const ADAPTIVE_GAMES: usize = 10;
+/// How much the blunder probability changes after a game for each point
+/// between the win rate of the opponent and the target one
+const ADAPTIVE_STEP: f64 = 0.2;
+
+impl Difficulty {
+    /// The highest value of the slider, it never loses on small playgrounds
+    pub const MAX_SLIDER: u32 = SLIDER.len() as u32;
+
+    /// Creates new Difficulty and returns it
+    pub const fn new(depth: Option<usize>, blunder: f64, block: bool) -> Difficulty {
+        Difficulty {
+            depth,
+            blunder,
+            block,
+        }
+    }
+    /// Returns the Difficulty of the slider value, 1 plays random moves and
+    /// MAX_SLIDER never loses, values out of the range are clamped
+    pub fn from_slider(value: u32) -> Difficulty {
+        SLIDER[value.clamp(1, Difficulty::MAX_SLIDER) as usize - 1]
+    }
+    /// Returns the slider value of the difficulty or None if it is not on the slider
+    pub fn get_slider(&self) -> Option<u32> {
+        (1..=Difficulty::MAX_SLIDER).find(|&value| Difficulty::from_slider(value) == *self)
+    }
+    /// Returns the slider value of the difficulty or of the nearest one on the slider,
+    /// the depth counts first, then the blunder probability and whether it blocks
+    pub fn get_nearest_slider(&self) -> u32 {
+        let depth = |difficulty: &Difficulty| difficulty.depth.unwrap_or(usize::MAX);
+        let distance = |value: u32| {
+            let other = Difficulty::from_slider(value);
+            (
+                depth(&other).abs_diff(depth(self)),
+                (other.blunder - self.blunder).abs(),
+                other.block != self.block,
+            )
+        };
+        (1..=Difficulty::MAX_SLIDER)
+            .min_by(|&a, &b| {
+                distance(a)
+                    .partial_cmp(&distance(b))
+                    .unwrap_or(Ordering::Equal)
+            })
+            .unwrap_or(1)
+    }
+}
+impl From<Level> for Difficulty {
+    fn from(level: Level) -> Difficulty {
+        Difficulty::from_slider(level.get_slider())
+    }
+}
+impl Level {
+    /// returns the slider value of the preset
+    pub fn get_slider(self) -> u32 {
+        match self {
+            Level::Stupid => 1,
+            Level::Normal => 4,
+            Level::Smart => 7,
+            Level::Perfect => Difficulty::MAX_SLIDER,
+        }
+    }
+    /// Returns the preset of the slider value or None if there is not any
+    pub fn from_slider(value: u32) -> Option<Level> {
+        [Level::Stupid, Level::Normal, Level::Smart, Level::Perfect]
+            .iter()
+            .copied()
+            .find(|level| level.get_slider() == value)
+    }
+}
+
+/// Blunder probability that follows the results of the opponent
+struct Adaptive {
+    /// win rate of the opponent to reach
+    target: f64,
+    blunder: f64,
+    /// last results of the opponent, the oldest first
+    results: VecDeque<Outcome>,
+}
+
+/// Computer opponent that plays one of the players in a chosen Difficulty
 pub struct ComputerPlayer {
     player_turn: Cell,
     active: bool,
-    level: Level,
+    difficulty: Difficulty,
+    adaptive: Option<Adaptive>,
+    rng: Rng,
+}
+impl Default for ComputerPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
-
 impl ComputerPlayer {
+    /// Creates new inactive ComputerPlayer that plays O in the stupid level
     pub fn new() -> ComputerPlayer {
         ComputerPlayer {
             player_turn: Cell::O,
             active: false,
-            level: Level::Stupid,
+            difficulty: Difficulty::from(Level::Stupid),
+            adaptive: None,
+            rng: Rng::from_time(),
         }
     }
 
+    /// Sets which player the computer plays
     pub fn set_player_turn(&mut self, player_turn: Cell) {
         self.player_turn = player_turn;
     }
+    /// Sets whether play_turn plays or does nothing
     pub fn set_active(&mut self, active: bool) {
         self.active = active;
     }
+    /// Sets how well the computer plays to one of the presets
     pub fn set_level(&mut self, level: Level) {
-        self.level = level;
+        self.difficulty = Difficulty::from(level);
+    }
+    /// Sets how the computer chooses its moves
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = difficulty;
+    }
+    /// Sets the win rate from 0 to 1 the opponent should reach, the blunder probability
+    /// then starts from the one of the difficulty and follows the results of the opponent,
+    /// None keeps the one of the difficulty
+    pub fn set_adaptive(&mut self, target: Option<f64>) {
+        self.adaptive = target.map(|target| Adaptive {
+            target: target.clamp(0.0, 1.0),
+            blunder: self.difficulty.blunder,
+            results: VecDeque::new(),
+        });
+    }
+    /// Restarts adaptive mode from the blunder probability and the last results
+    /// of the opponent it reached before, it does nothing if the mode is not adaptive
+    pub fn resume_adaptive(&mut self, blunder: f64, results: &[Outcome]) {
+        if let Some(adaptive) = self.adaptive.as_mut() {
+            adaptive.blunder = blunder.clamp(0.0, 1.0);
+            let start = results.len().saturating_sub(ADAPTIVE_GAMES);
+            adaptive.results = results[start..].iter().copied().collect();
+        }
+    }
+    /// Adds the result of a game for the opponent and moves the blunder probability
+    /// toward the target win rate in adaptive mode, it does nothing otherwise
+    ///
+    /// Only wins count, so an opponent who keeps drawing gets more mistakes.
+    pub fn add_result(&mut self, result: Outcome) {
+        let adaptive = match self.adaptive.as_mut() {
+            Some(adaptive) => adaptive,
+            None => return,
+        };
+        if adaptive.results.len() == ADAPTIVE_GAMES {
+            adaptive.results.pop_front();
+        }
+        adaptive.results.push_back(result);
+        let wins = adaptive
+            .results
+            .iter()
+            .filter(|&&result| result == Outcome::Win)
+            .count();
+        let win_rate = wins as f64 / adaptive.results.len() as f64;
+        let blunder = adaptive.blunder + ADAPTIVE_STEP * (adaptive.target - win_rate);
+        adaptive.blunder = blunder.clamp(0.0, 1.0);
+    }
+    /// Restarts the random moves from seed so the same games can be played again
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
     }
+    /// returns how the computer chooses its moves
+    pub fn get_difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+    /// returns the target win rate of the opponent or None if the mode is not adaptive
+    pub fn get_adaptive(&self) -> Option<f64> {
+        self.adaptive.as_ref().map(|adaptive| adaptive.target)
+    }
+    /// returns the probability of choosing a move that is worse than the best one found
+    pub fn get_blunder(&self) -> f64 {
+        match self.adaptive.as_ref() {
+            Some(adaptive) => adaptive.blunder,
+            None => self.difficulty.blunder,
+        }
+    }
+    /// returns whether play_turn plays or does nothing
     pub fn get_active(&self) -> bool {
         self.active
     }
+    /// returns the player the computer plays against
     pub fn get_opponent(&self) -> Cell {
-        match self.player_turn {
-            Cell::X => Cell::O,
-            Cell::O => Cell::X,
-            _ => Cell::Empty,
-        }
+        self.player_turn.opponent()
+    }
+
+    /// returns which player the computer plays
+    pub fn get_player_turn(&self) -> Cell {
+        self.player_turn
     }
 
-    pub fn play_turn(&self, controller: &mut Controller) {
-        if !self.active || self.player_turn != controller.get_player_turn() {
+    /// Plays the computer move if the computer player is active and it is its turn
+    pub fn play_turn(&mut self, controller: &mut Controller) {
+        if !self.active
+            || self.player_turn != controller.get_player_turn()
+            || controller.status() != GameStatus::InProgress
+        {
             return;
         }
+        let m = self.choose_move(&Position::from(&*controller));
+        controller.play_turn(m.row, m.column);
+    }
 
-        match self.level {
-            Level::Smart => {
-                let (row, column) = ComputerPlayer::normal_play(controller, self.player_turn);
-                if row != 0 {
-                    controller.play_turn(row, column);
-                    return;
-                }
-                let (row, column) = ComputerPlayer::normal_play(controller, self.get_opponent());
-                if row != 0 {
-                    controller.play_turn(row, column);
-                    return;
-                }
-                let (row, column) = self.smart_play(controller);
-                if row != 0 {
-                    controller.play_turn(row, column);
-                    return;
-                }
-                let (row, column) = ComputerPlayer::stupid_play(controller);
-                controller.play_turn(row, column);
-            }
-            Level::Normal => {
-                let (row, column) = ComputerPlayer::normal_play(controller, self.player_turn);
-                if row != 0 {
-                    controller.play_turn(row, column);
-                    return;
-                }
-                let (row, column) = ComputerPlayer::normal_play(controller, self.get_opponent());
-                if row != 0 {
-                    controller.play_turn(row, column);
-                    return;
-                }
-                let (row, column) = ComputerPlayer::stupid_play(controller);
-                controller.play_turn(row, column);
-            }
-            Level::Stupid => {
-                let (row, column) = ComputerPlayer::stupid_play(controller);
-                controller.play_turn(row, column);
-            }
+    /// Returns the moves that block a line the opponent completes next move
+    /// or all the moves if there is not any or player can win at once
+    fn keep_blocks(position: &Position, moves: Vec<(usize, bool)>) -> Vec<(usize, bool)> {
+        let player = position.get_player_turn();
+        let opponent = player.opponent();
+        if moves
+            .iter()
+            .any(|&(index, _)| position.is_winning_index(index, player))
+        {
+            return moves;
+        }
+        let blocks: Vec<(usize, bool)> = moves
+            .iter()
+            .copied()
+            .filter(|&(index, _)| position.is_winning_index(index, opponent))
+            .collect();
+        if blocks.is_empty() {
+            moves
+        } else {
+            blocks
         }
     }
-
-    fn stupid_play(controller: &mut Controller) -> (usize, usize) {
-        loop {
-            let row = rand::generate_range(1, 4) as usize;
-            let column = rand::generate_range(1, 4) as usize;
-
-            if controller.is_free(row, column) {
-                return (row, column);
-            }
+    /// Returns a random index among the best moves, or among the worse ones
+    /// with the blunder probability, or None if there is no move
+    fn pick(&mut self, moves: &[(usize, bool)]) -> Option<usize> {
+        let (mut best_moves, worse_moves): (Vec<&(usize, bool)>, Vec<_>) =
+            moves.iter().partition(|&&(_, is_best)| is_best);
+        if best_moves.is_empty() {
+            // the best moves were left out by keep_blocks
+            best_moves = worse_moves.clone();
+        }
+        let moves = if !worse_moves.is_empty() && self.rng.generate_fraction() < self.get_blunder()
+        {
+            worse_moves
+        } else {
+            best_moves
+        };
+        if moves.is_empty() {
+            return None;
         }
+        let choice = self.rng.generate_range(0, moves.len() as u64) as usize;
+        Some(moves[choice].0)
     }
-    fn normal_play(controller: &mut Controller, player: Cell) -> (usize, usize) {
-        for i in 1..4 {
-            for j in 1..4 {
-                if controller.get_content(i, j) != player {
-                    continue;
-                }
-                if controller.get_content(i, j) == controller.get_content(i + 1, j) {
-                    if i + 2 < 4 && controller.get_content(i + 2, j) == Cell::Empty {
-                        return (i + 2, j);
-                    }
-                    if i - 1 > 0 && controller.get_content(i - 1, j) == Cell::Empty {
-                        return (i - 1, j);
-                    }
-                }
-                if controller.get_content(i, j) == controller.get_content(i, j + 1) {
-                    if j + 2 < 4 && controller.get_content(i, j + 2) == Cell::Empty {
-                        return (i, j + 2);
-                    }
-                    if j - 1 > 0 && controller.get_content(i, j - 1) == Cell::Empty {
-                        return (i, j - 1);
-                    }
-                }
-                if controller.get_content(i, j) == controller.get_content(i + 2, j)
-                    && controller.get_content(i + 1, j) == Cell::Empty
-                {
-                    return (i + 1, j);
-                }
-                if controller.get_content(i, j) == controller.get_content(i, j + 2)
-                    && controller.get_content(i, j + 1) == Cell::Empty
-                {
-                    return (i, j + 1);
-                }
-            }
+}
+
+impl Player for ComputerPlayer {
+    /// Returns the move of the computer for the player whose turn it is
+    fn choose_move(&mut self, position: &Position) -> Move {
+        let mut search = Search::new();
+        search.set_depth(self.difficulty.depth);
+        search.limit_nodes(position, SEARCH_NODES);
+        let mut moves = search.best_moves(position);
+        if self.difficulty.block {
+            moves = ComputerPlayer::keep_blocks(position, moves);
         }
-        let top_left = controller.get_content(1, 1);
-        let top_right = controller.get_content(3, 1);
-        let bottom_left = controller.get_content(1, 3);
-        let bottom_right = controller.get_content(3, 3);
-        let center = controller.get_content(2, 2);
+        let (row, column) = match self.pick(&moves) {
+            Some(index) => position.location(index),
+            None => (0, 0),
+        };
+        Move::new(position.get_player_turn(), row, column)
+    }
+}
 
-        if top_left == player && top_left == center && bottom_right == Cell::Empty {
-            return (3, 3); // bottom_right
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays every possible opponent line against the computer player
+    /// and panics if the computer player ever loses.
+    fn play_all_lines(controller: &Controller, cp: &mut ComputerPlayer) {
+        let winner = controller.get_winner();
+        if winner != Cell::Empty {
+            assert!(
+                winner != cp.get_opponent(),
+                "computer player lost as {}",
+                cp.player_turn.to_str()
+            );
+            return;
         }
-        if center == player && center == bottom_right && top_left == Cell::Empty {
-            return (1, 1); // top_left
+        if !controller.can_continue() {
+            return;
         }
-        if top_left == player && top_left == bottom_right && center == Cell::Empty {
-            return (2, 2); // center
+        if controller.get_player_turn() == cp.player_turn {
+            let mut child = controller.clone();
+            cp.play_turn(&mut child);
+            play_all_lines(&child, cp);
+            return;
         }
-        if bottom_left == player && bottom_left == center && top_right == Cell::Empty {
-            return (3, 1); // top_right
+        for row in 1..4 {
+            for column in 1..4 {
+                if controller.is_free(row, column) {
+                    let mut child = controller.clone();
+                    child.play_turn(row, column);
+                    play_all_lines(&child, cp);
+                }
+            }
         }
-        if top_right == player && top_right == center && bottom_left == Cell::Empty {
-            return (1, 3); // bottom_left
+    }
+
+    fn perfect_player(player_turn: Cell) -> ComputerPlayer {
+        let mut cp = ComputerPlayer::new();
+        cp.set_active(true);
+        cp.set_level(Level::Perfect);
+        cp.set_player_turn(player_turn);
+        cp
+    }
+
+    #[test]
+    fn adaptive_follows_opponent_results() {
+        let mut cp = perfect_player(Cell::O);
+        cp.set_adaptive(Some(0.5));
+        for _ in 0..5 {
+            cp.add_result(Outcome::Draw);
         }
-        if bottom_left == player && bottom_left == top_right && center == Cell::Empty {
-            return (2, 2); // center
+        let blunder = cp.get_blunder();
+        assert!(blunder > 0.0);
+        for _ in 0..10 {
+            cp.add_result(Outcome::Win);
         }
-        (0, 0)
+        assert!(cp.get_blunder() < blunder);
+        cp.set_adaptive(None);
+        assert_eq!(cp.get_blunder(), 0.0);
     }
-    fn smart_play(&self, controller: &mut Controller) -> (usize, usize) {
-        let top_left = controller.get_content(1, 1);
-        let top_center = controller.get_content(2, 1);
-        let top_right = controller.get_content(3, 1);
 
-        let bottom_left = controller.get_content(1, 3);
-        let bottom_center = controller.get_content(2, 3);
-        let bottom_right = controller.get_content(3, 3);
+    #[test]
+    fn perfect_never_loses_as_x() {
+        play_all_lines(&Controller::new(), &mut perfect_player(Cell::X));
+    }
 
-        let center_left = controller.get_content(1, 2);
-        let center = controller.get_content(2, 2);
-        let center_right = controller.get_content(3, 2);
+    #[test]
+    fn perfect_never_loses_as_o() {
+        play_all_lines(&Controller::new(), &mut perfect_player(Cell::O));
+    }
 
-        // Main Move
-        if center == Cell::Empty {
-            return (2, 2); // center
-        }
+    #[test]
+    fn perfect_takes_immediate_win() {
+        let mut controller = Controller::new();
+        // X: (1, 1), (2, 1)  O: (1, 2), (2, 2)
+        controller.play_turn(1, 1);
+        controller.play_turn(1, 2);
+        controller.play_turn(2, 1);
+        controller.play_turn(2, 2);
+        perfect_player(Cell::X).play_turn(&mut controller);
+        assert!(controller.get_winner() == Cell::X);
+    }
 
-        // Defense
+    #[test]
+    fn perfect_blocks_opponent_win() {
+        let mut controller = Controller::new();
+        // X: (1, 1), (2, 1)  O: (2, 2)
+        controller.play_turn(1, 1);
+        controller.play_turn(2, 2);
+        controller.play_turn(2, 1);
+        perfect_player(Cell::O).play_turn(&mut controller);
+        assert!(controller.get_content(3, 1) == Cell::O);
+    }
 
-        // From first strategy
-        if bottom_left == Cell::Empty
-            && top_right == Cell::Empty
-            && bottom_right == Cell::Empty
-            && top_left == Cell::Empty
-        {
-            return (1, 1); // top_left
+    #[test]
+    fn finds_nearest_slider_value() {
+        for value in 1..=Difficulty::MAX_SLIDER {
+            assert_eq!(Difficulty::from_slider(value).get_nearest_slider(), value);
         }
+        assert_eq!(Difficulty::new(Some(4), 0.05, true).get_nearest_slider(), 8);
+        assert_eq!(Difficulty::new(Some(20), 0.0, true).get_nearest_slider(), 9);
+        assert_eq!(Difficulty::new(None, 0.5, false).get_nearest_slider(), 10);
+        assert_eq!(Difficulty::new(Some(0), 0.0, true).get_nearest_slider(), 1);
+    }
 
-        // From second strategy
-        if top_left == self.player_turn
-            && center == self.get_opponent()
-            && bottom_right == self.get_opponent()
-            && top_right == Cell::Empty
-        {
-            return (3, 1); // top_right
-        }
-        // From special case
-        if top_left == self.get_opponent()
-            && center == self.player_turn
-            && bottom_right == self.get_opponent()
-            && center_right == Cell::Empty
-        {
-            return (3, 2); // center_right
+    #[test]
+    fn levels_and_slider_values_match() {
+        for level in [Level::Stupid, Level::Normal, Level::Smart, Level::Perfect] {
+            assert_eq!(Level::from_slider(level.get_slider()), Some(level));
+            let difficulty = Difficulty::from(level);
+            assert_eq!(difficulty.get_slider(), Some(level.get_slider()));
         }
-        if top_right == self.get_opponent()
-            && center == self.player_turn
-            && bottom_left == self.get_opponent()
-            && center_left == Cell::Empty
-        {
-            return (1, 2); // center_left
+        for value in 1..=Difficulty::MAX_SLIDER {
+            assert_eq!(Difficulty::from_slider(value).get_slider(), Some(value));
         }
+        assert_eq!(Level::from_slider(5), None);
+        assert_eq!(Difficulty::from_slider(0), Difficulty::from_slider(1));
+        assert_eq!(
+            Difficulty::from_slider(99),
+            Difficulty::from(Level::Perfect)
+        );
+    }
 
-        // Attack
-
-        if center != self.player_turn {
-            return (0, 0);
+    #[test]
+    fn normal_always_blocks_a_single_line() {
+        for seed in 0..50 {
+            let mut controller = Controller::new();
+            // X: (1, 1), (2, 1)  O: (2, 2)
+            controller.play_turn(1, 1);
+            controller.play_turn(2, 2);
+            controller.play_turn(2, 1);
+            let mut cp = perfect_player(Cell::O);
+            cp.set_level(Level::Normal);
+            cp.set_seed(seed);
+            cp.play_turn(&mut controller);
+            assert!(controller.get_content(3, 1) == Cell::O, "seed {}", seed);
         }
+    }
 
-        // First strategy
-        if top_left == self.player_turn
-            && center_left == self.get_opponent()
-            && top_right == Cell::Empty
-        {
-            return (3, 1); // top_right
+    #[test]
+    fn adaptive_blunder_stays_a_probability() {
+        let mut cp = perfect_player(Cell::O);
+        cp.set_adaptive(Some(1.0));
+        for _ in 0..20 {
+            cp.add_result(Outcome::Loss);
         }
-        if top_left == self.player_turn
-            && (top_center == self.get_opponent()
-                || bottom_center == self.get_opponent()
-                || center_right == self.get_opponent())
-            && bottom_left == Cell::Empty
-        {
-            return (1, 3); // bottom_left
+        assert_eq!(cp.get_blunder(), 1.0);
+        cp.set_adaptive(Some(0.0));
+        cp.resume_adaptive(0.3, &[]);
+        for _ in 0..20 {
+            cp.add_result(Outcome::Win);
         }
+        assert_eq!(cp.get_blunder(), 0.0);
+        cp.set_adaptive(Some(7.0));
+        assert_eq!(cp.get_adaptive(), Some(1.0));
+    }
 
-        // Second strategy
-        if bottom_left == self.get_opponent()
-            && top_right == Cell::Empty
-            && bottom_right == Cell::Empty
-            && top_left == Cell::Empty
-        {
-            return (3, 1); // top_right
-        }
-        if bottom_left == Cell::Empty
-            && top_right == self.get_opponent()
-            && bottom_right == Cell::Empty
-            && top_left == Cell::Empty
-        {
-            return (1, 3); // bottom_left
-        }
-        if bottom_left == Cell::Empty
-            && top_right == Cell::Empty
-            && bottom_right == self.get_opponent()
-            && top_left == Cell::Empty
-        {
-            return (1, 1); // top_left
-        }
-        if bottom_left == Cell::Empty
-            && top_right == Cell::Empty
-            && bottom_right == Cell::Empty
-            && top_left == self.get_opponent()
-        {
-            return (3, 3); // bottom_right
-        }
-        if top_left == self.player_turn && bottom_right == self.get_opponent() {
-            if center_left == self.get_opponent() && top_right == Cell::Empty {
-                return (3, 1); // top_right
-            }
-            if top_center == self.get_opponent() && bottom_left == Cell::Empty {
-                return (1, 3); // bottom_left
-            }
-        }
-        if bottom_right == self.player_turn && top_left == self.get_opponent() {
-            if bottom_center == self.get_opponent() && top_right == Cell::Empty {
-                return (3, 1); // top_right
-            }
-            if center_right == self.get_opponent() && bottom_left == Cell::Empty {
-                return (1, 3); // bottom_left
-            }
-        }
-        if top_right == self.player_turn && bottom_left == self.get_opponent() {
-            if center_left == self.get_opponent() && bottom_right == Cell::Empty {
-                return (3, 3); // bottom_right
-            }
-            if bottom_center == self.get_opponent() && top_left == Cell::Empty {
-                return (1, 1); // top_left
-            }
-            if top_center == self.get_opponent() && bottom_right == Cell::Empty {
-                return (3, 3); // bottom_right
-            }
-            if center_right == self.get_opponent() && top_left == Cell::Empty {
-                return (1, 1); // top_left
-            }
+    #[test]
+    fn adaptive_counts_only_the_last_games() {
+        let mut cp = perfect_player(Cell::O);
+        cp.set_adaptive(Some(0.5));
+        let mut results = vec![Outcome::Loss; 30];
+        results.extend(vec![Outcome::Win; ADAPTIVE_GAMES]);
+        cp.resume_adaptive(0.5, &results);
+        // the window only has wins, so one more win lowers the blunder by half a step
+        cp.add_result(Outcome::Win);
+        assert!((cp.get_blunder() - (0.5 - ADAPTIVE_STEP * 0.5)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn perfect_does_not_blunder_without_adaptive_mode() {
+        let mut cp = perfect_player(Cell::O);
+        for _ in 0..10 {
+            cp.add_result(Outcome::Loss);
         }
-        (0, 0)
+        assert_eq!(cp.get_blunder(), 0.0);
+        assert_eq!(cp.get_adaptive(), None);
     }
 }
@@ -6,12 +6,51 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at http://mozilla.org/MPL/2.0/.
 */
 
-use crate::game_core::Cell;
+use crate::game_core::{Cell, GameStatus, Move, MAX_CELLS};
+use std::error::Error;
+use std::fmt;
+
+/// The reasons for refusing a move
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum MoveError {
+    /// The location is outside the playground
+    OutOfBounds,
+    /// The cell already contains X or O
+    Occupied,
+    /// The game has already a winner or no free cells
+    GameOver,
+    /// It is not the turn of the player who made the move
+    WrongPlayer,
+}
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let message = match self {
+            MoveError::OutOfBounds => "Choose location inside the playground!",
+            MoveError::Occupied => "Choose empty location!",
+            MoveError::GameOver => "The game is over!",
+            MoveError::WrongPlayer => "It is not your turn!",
+        };
+        f.write_str(message)
+    }
+}
+impl Error for MoveError {}
 
 /// Controls the game and contains the playground and player turn
+///
+/// The playground is `width` cells wide and `height` cells high and a player
+/// wins by filling `win_length` consecutive cells horizontally, vertically
+/// or diagonally. Locations are 1-based: `row` counts cells from the left
+/// and `column` counts cells from the top.
+#[derive(Clone, Debug)]
 pub struct Controller {
-    playground: [[Cell; 3]; 3],
+    playground: Vec<Cell>,
+    width: usize,
+    height: usize,
+    win_length: usize,
     player_turn: Cell,
+    first_player: Cell,
+    history: Vec<(usize, usize)>,
+    undone: Vec<(usize, usize)>,
 }
 impl Default for Controller {
     fn default() -> Self {
@@ -19,98 +58,493 @@ impl Default for Controller {
     }
 }
 impl Controller {
-    /// Creates new Controller and returns it
+    /// Creates new classic 3x3 Controller and returns it
     pub fn new() -> Controller {
+        Controller::with_size(3, 3, 3)
+    }
+    /// Creates new Controller with a `width` x `height` playground where
+    /// `win_length` cells in a line are needed to win
+    ///
+    /// Panics if any of the parameters is zero, if `win_length` does not
+    /// fit in the playground or if it has more than MAX_CELLS cells.
+    pub fn with_size(width: usize, height: usize, win_length: usize) -> Controller {
+        assert!(width > 0 && height > 0, "playground must not be empty");
+        assert!(
+            width.saturating_mul(height) <= MAX_CELLS,
+            "playground is too big"
+        );
+        assert!(
+            win_length > 0 && win_length <= width.max(height),
+            "win length must fit in the playground"
+        );
         Controller {
-            playground: [[Cell::Empty; 3]; 3],
+            playground: vec![Cell::Empty; width * height],
+            width,
+            height,
+            win_length,
             player_turn: Cell::X,
+            first_player: Cell::X,
+            history: Vec::new(),
+            undone: Vec::new(),
         }
     }
-    /// Initialize the Controller
+    /// Initialize the Controller keeping the playground size and the first player
     pub fn initialize(&mut self) {
-        *self = Controller::new();
+        let first_player = self.first_player;
+        *self = Controller::with_size(self.width, self.height, self.win_length);
+        self.first_player = first_player;
+        self.player_turn = first_player;
+    }
+    /// Sets which player plays the first move and starts a new game
+    ///
+    /// Panics if first_player is Cell::Empty.
+    pub fn set_first_player(&mut self, first_player: Cell) {
+        assert!(first_player != Cell::Empty, "first player must be X or O");
+        self.first_player = first_player;
+        self.initialize();
+    }
+    /// returns which player plays the first move
+    pub fn get_first_player(&self) -> Cell {
+        self.first_player
+    }
+    /// returns the number of cells in each line of the playground
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+    /// returns the number of lines of the playground
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+    /// returns how many cells in a line are needed to win
+    pub fn get_win_length(&self) -> usize {
+        self.win_length
+    }
+    /// Checks if row and column are inside the playground
+    fn is_inside(&self, row: usize, column: usize) -> bool {
+        row >= 1 && column >= 1 && row <= self.width && column <= self.height
+    }
+    /// Returns the position of the cell in the playground vector
+    fn index(&self, row: usize, column: usize) -> usize {
+        (column - 1) * self.width + (row - 1)
     }
     /// Takes row and column parameters and returns the content of the cell with this location
     pub fn get_content(&self, row: usize, column: usize) -> Cell {
-        if row > 3 || column > 3 || row < 1 || column < 1 {
+        if !self.is_inside(row, column) {
             return Cell::Empty;
         }
-        self.playground[row - 1][column - 1]
+        self.playground[self.index(row, column)]
     }
     /// returns which player will play next
     pub fn get_player_turn(&self) -> Cell {
         self.player_turn
     }
+    /// Sets which player will play next
+    pub(crate) fn set_player_turn(&mut self, player_turn: Cell) {
+        self.player_turn = player_turn;
+    }
+    /// Puts content in the cell with this location without recording a move
+    pub(crate) fn set_content(&mut self, row: usize, column: usize, content: Cell) {
+        let index = self.index(row, column);
+        self.playground[index] = content;
+    }
+    /// returns the played locations from the first move to the last one
+    pub fn get_history(&self) -> &[(usize, usize)] {
+        &self.history
+    }
+    /// returns how many undone moves can be played again by redo
+    pub fn get_redo_count(&self) -> usize {
+        self.undone.len()
+    }
     /// Puts X or O in the cell that is in row and column of the parameters
     pub fn play_turn(&mut self, row: usize, column: usize) {
+        self.put(row, column);
+        self.undone.clear();
+    }
+    /// Plays the move if it is legal and returns the state of the game after it
+    pub fn try_play(&mut self, m: Move) -> Result<GameStatus, MoveError> {
+        if self.status() != GameStatus::InProgress {
+            return Err(MoveError::GameOver);
+        }
+        if m.player != self.player_turn {
+            return Err(MoveError::WrongPlayer);
+        }
+        if !self.is_inside(m.row, m.column) {
+            return Err(MoveError::OutOfBounds);
+        }
+        if !self.is_free(m.row, m.column) {
+            return Err(MoveError::Occupied);
+        }
+        self.play_turn(m.row, m.column);
+        Ok(self.status())
+    }
+    /// Returns whether the game is still running, won or drawn
+    ///
+    /// A full playground that contains a winning line is a win.
+    pub fn status(&self) -> GameStatus {
+        if let Some(line) = self.winning_line() {
+            let (row, column) = line[0];
+            GameStatus::Win {
+                player: self.get_content(row, column),
+                line,
+            }
+        } else if self.can_continue() {
+            GameStatus::InProgress
+        } else {
+            GameStatus::Draw
+        }
+    }
+    /// Puts the current player in the cell and records the move
+    fn put(&mut self, row: usize, column: usize) {
+        let index = self.index(row, column);
         match self.player_turn {
-            Cell::X => self.playground[row - 1][column - 1] = Cell::X,
-            Cell::O => self.playground[row - 1][column - 1] = Cell::O,
+            Cell::X => self.playground[index] = Cell::X,
+            Cell::O => self.playground[index] = Cell::O,
             _ => {}
         }
+        self.history.push((row, column));
         self.change_turn();
     }
+    /// Takes back the last move and returns false if there is no move to take back
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((row, column)) => {
+                let index = self.index(row, column);
+                self.playground[index] = Cell::Empty;
+                self.change_turn();
+                self.undone.push((row, column));
+                true
+            }
+            None => false,
+        }
+    }
+    /// Plays the last undone move again and returns false if there is no move to play
+    pub fn redo(&mut self) -> bool {
+        match self.undone.pop() {
+            Some((row, column)) => {
+                self.put(row, column);
+                true
+            }
+            None => false,
+        }
+    }
     /// Changes player turn
     fn change_turn(&mut self) {
-        match self.player_turn {
-            Cell::X => self.player_turn = Cell::O,
-            Cell::O => self.player_turn = Cell::X,
-            _ => {}
-        }
+        self.player_turn = self.player_turn.opponent();
     }
     /// Checks if a certain cell is free(empty) or no and returns the answer
     pub fn is_free(&self, row: usize, column: usize) -> bool {
-        if row < 1
-            || column < 1
-            || row > 3
-            || column > 3
-            || self.playground[row - 1][column - 1] != Cell::Empty
-        {
-            return false;
-        }
-        true
+        self.is_inside(row, column) && self.playground[self.index(row, column)] == Cell::Empty
     }
     /// Checks if there is a chance to continue playing or no and returns the answer
     pub fn can_continue(&self) -> bool {
-        for row in self.playground.iter() {
-            for cell in row.iter() {
-                if cell == &Cell::Empty {
-                    return true;
-                }
-            }
-        }
-        false
+        self.playground.iter().any(|cell| cell == &Cell::Empty)
     }
     /// Checks if there is a winner or no if yes it returns the winner else it returns Cell::Empty
     pub fn get_winner(&self) -> Cell {
-        for i in 0..3 {
-            if self.playground[0][i] != Cell::Empty
-                && self.playground[0][i] == self.playground[1][i]
-                && self.playground[0][i] == self.playground[2][i]
-            {
-                return self.playground[0][i];
-            }
+        match self.status() {
+            GameStatus::Win { player, .. } => player,
+            _ => Cell::Empty,
         }
-        for i in 0..3 {
-            if self.playground[i][0] != Cell::Empty
-                && self.playground[i][0] == self.playground[i][1]
-                && self.playground[i][0] == self.playground[i][2]
-            {
-                return self.playground[i][0];
+    }
+    /// Returns the locations of the first win_length cells of a winning line if there is one
+    fn winning_line(&self) -> Option<Vec<(usize, usize)>> {
+        for column in 1..=self.height {
+            for row in 1..=self.width {
+                let player = self.get_content(row, column);
+                if player == Cell::Empty {
+                    continue;
+                }
+                for &(d_row, d_column) in DIRECTIONS.iter() {
+                    let count = 1 + self.count_in_direction(row, column, d_row, d_column, player);
+                    if count >= self.win_length {
+                        let line = (0..self.win_length as isize)
+                            .map(|i| {
+                                (
+                                    (row as isize + i * d_row) as usize,
+                                    (column as isize + i * d_column) as usize,
+                                )
+                            })
+                            .collect();
+                        return Some(line);
+                    }
+                }
             }
         }
-        if self.playground[0][0] != Cell::Empty
-            && self.playground[0][0] == self.playground[1][1]
-            && self.playground[0][0] == self.playground[2][2]
-        {
-            return self.playground[0][0];
+        None
+    }
+    /// Checks if putting player in the free cell with this location makes a winning line
+    pub fn is_winning_cell(&self, row: usize, column: usize, player: Cell) -> bool {
+        player != Cell::Empty
+            && self.is_free(row, column)
+            && self.longest_line(row, column, player) >= self.win_length
+    }
+    /// Returns the length of the longest line of player cells passing through
+    /// the location, counting the location itself as a player cell
+    fn longest_line(&self, row: usize, column: usize, player: Cell) -> usize {
+        let mut longest = 0;
+        for &(d_row, d_column) in DIRECTIONS.iter() {
+            let length = 1
+                + self.count_in_direction(row, column, d_row, d_column, player)
+                + self.count_in_direction(row, column, -d_row, -d_column, player);
+            longest = longest.max(length);
         }
-        if self.playground[0][2] != Cell::Empty
-            && self.playground[0][2] == self.playground[1][1]
-            && self.playground[0][2] == self.playground[2][0]
-        {
-            return self.playground[0][2];
+        longest
+    }
+    /// Counts the player cells next to the location in one direction
+    fn count_in_direction(
+        &self,
+        row: usize,
+        column: usize,
+        d_row: isize,
+        d_column: isize,
+        player: Cell,
+    ) -> usize {
+        let mut count = 0;
+        let mut row = row as isize + d_row;
+        let mut column = column as isize + d_column;
+        while row >= 1 && column >= 1 && self.get_content(row as usize, column as usize) == player {
+            count += 1;
+            row += d_row;
+            column += d_column;
         }
-        Cell::Empty
+        count
+    }
+}
+
+/// Horizontal, vertical and both diagonal directions of a line
+const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays the (row, column) locations in turn starting from the player whose turn it is
+    fn play(controller: &mut Controller, moves: &[(usize, usize)]) {
+        for &(row, column) in moves.iter() {
+            controller.play_turn(row, column);
+        }
+    }
+
+    #[test]
+    fn wins_horizontally_on_non_square_playground() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        // X: (1, 1), (2, 1), (3, 1)  O: (1, 3), (2, 3)
+        play(&mut controller, &[(1, 1), (1, 3), (2, 1), (2, 3)]);
+        assert_eq!(controller.get_winner(), Cell::Empty);
+        play(&mut controller, &[(3, 1)]);
+        assert_eq!(controller.get_winner(), Cell::X);
+    }
+
+    #[test]
+    fn wins_vertically_on_non_square_playground() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        // X: (5, 2), (5, 3), (5, 4)  O: (1, 1), (2, 1)
+        play(&mut controller, &[(5, 2), (1, 1), (5, 3), (2, 1), (5, 4)]);
+        assert_eq!(controller.get_winner(), Cell::X);
+        assert!(!controller.is_free(5, 4));
+        assert!(controller.can_continue());
+    }
+
+    #[test]
+    fn wins_diagonally_on_non_square_playground() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        // X: (1, 2), (2, 3), (3, 4)  O: (5, 1), (4, 1)
+        play(&mut controller, &[(1, 2), (5, 1), (2, 3), (4, 1), (3, 4)]);
+        assert_eq!(controller.get_winner(), Cell::X);
+    }
+
+    #[test]
+    fn wins_anti_diagonally_on_non_square_playground() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        // X: (2, 4), (3, 3), (4, 2)  O: (1, 1), (5, 4)
+        play(&mut controller, &[(2, 4), (1, 1), (3, 3), (5, 4), (4, 2)]);
+        assert_eq!(controller.get_winner(), Cell::X);
+    }
+
+    #[test]
+    fn locations_out_of_range_are_not_free() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        play(&mut controller, &[(5, 4)]);
+        assert_eq!(controller.get_content(5, 4), Cell::X);
+        for &(row, column) in [(0, 1), (1, 0), (6, 1), (1, 5), (6, 5)].iter() {
+            assert_eq!(controller.get_content(row, column), Cell::Empty);
+            assert!(!controller.is_free(row, column));
+        }
+    }
+
+    #[test]
+    fn full_playground_without_line_is_draw() {
+        let mut controller = Controller::with_size(3, 2, 3);
+        // X O X
+        // O X O
+        play(&mut controller, &[(1, 1), (2, 1), (3, 1), (1, 2), (2, 2)]);
+        assert!(controller.can_continue());
+        play(&mut controller, &[(3, 2)]);
+        assert!(!controller.can_continue());
+        assert_eq!(controller.get_winner(), Cell::Empty);
+        assert_eq!(controller.status(), GameStatus::Draw);
+    }
+
+    #[test]
+    fn undo_stops_at_the_start() {
+        let mut controller = Controller::new();
+        assert!(!controller.undo());
+        play(&mut controller, &[(2, 2)]);
+        assert!(controller.undo());
+        assert!(!controller.undo());
+        assert!(controller.get_history().is_empty());
+        assert_eq!(controller.get_player_turn(), Cell::X);
+        assert!(controller.is_free(2, 2));
+        assert!(controller.redo());
+        assert_eq!(controller.get_content(2, 2), Cell::X);
+        assert!(!controller.redo());
+    }
+
+    #[test]
+    fn new_move_clears_redo() {
+        let mut controller = Controller::new();
+        play(&mut controller, &[(1, 1), (2, 2)]);
+        assert!(controller.undo());
+        assert_eq!(controller.get_redo_count(), 1);
+        play(&mut controller, &[(3, 3)]);
+        assert_eq!(controller.get_redo_count(), 0);
+        assert!(!controller.redo());
+        assert_eq!(controller.get_history(), &[(1, 1), (3, 3)]);
+    }
+
+    #[test]
+    fn undo_after_win_reopens_game() {
+        let mut controller = Controller::new();
+        // X: (1, 1), (2, 1), (3, 1)  O: (1, 2), (2, 2)
+        play(&mut controller, &[(1, 1), (1, 2), (2, 1), (2, 2), (3, 1)]);
+        assert_eq!(controller.get_winner(), Cell::X);
+        assert!(controller.undo());
+        assert_eq!(controller.status(), GameStatus::InProgress);
+        assert_eq!(controller.get_player_turn(), Cell::X);
+        play(&mut controller, &[(3, 3)]);
+        assert_eq!(controller.get_winner(), Cell::Empty);
+        assert_eq!(controller.get_player_turn(), Cell::O);
+    }
+
+    /// Checks that the move is refused with error and nothing changes
+    fn assert_refused(controller: &mut Controller, m: Move, error: MoveError) {
+        let before = format!("{:?}", controller);
+        assert_eq!(controller.try_play(m), Err(error));
+        assert_eq!(format!("{:?}", controller), before);
+    }
+
+    #[test]
+    fn refuses_location_outside_playground() {
+        let mut controller = Controller::new();
+        for &(row, column) in [(0, 1), (1, 0), (4, 1), (1, 4)].iter() {
+            assert_refused(
+                &mut controller,
+                Move::new(Cell::X, row, column),
+                MoveError::OutOfBounds,
+            );
+        }
+    }
+
+    #[test]
+    fn refuses_occupied_cell() {
+        let mut controller = Controller::new();
+        play(&mut controller, &[(2, 2)]);
+        assert_refused(
+            &mut controller,
+            Move::new(Cell::O, 2, 2),
+            MoveError::Occupied,
+        );
+        assert_eq!(controller.get_content(2, 2), Cell::X);
+    }
+
+    #[test]
+    fn refuses_move_of_wrong_player() {
+        let mut controller = Controller::new();
+        assert_refused(
+            &mut controller,
+            Move::new(Cell::O, 2, 2),
+            MoveError::WrongPlayer,
+        );
+    }
+
+    #[test]
+    fn refuses_move_after_game_over() {
+        let mut controller = Controller::new();
+        play(&mut controller, &[(1, 1), (1, 2), (2, 1), (2, 2), (3, 1)]);
+        assert_refused(
+            &mut controller,
+            Move::new(Cell::O, 3, 3),
+            MoveError::GameOver,
+        );
+    }
+
+    #[test]
+    fn plays_legal_move() {
+        let mut controller = Controller::new();
+        let status = controller.try_play(Move::new(Cell::X, 2, 2));
+        assert_eq!(status, Ok(GameStatus::InProgress));
+        assert_eq!(controller.get_content(2, 2), Cell::X);
+        assert_eq!(controller.get_player_turn(), Cell::O);
+    }
+
+    /// Returns the locations of the winning line of the game sorted or nothing if there is not any
+    fn sorted_line(controller: &Controller) -> Vec<(usize, usize)> {
+        match controller.status() {
+            GameStatus::Win { mut line, .. } => {
+                line.sort();
+                line
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn winning_line_is_the_winning_cells() {
+        let mut controller = Controller::new();
+        // X: (1, 1), (2, 2), (3, 3)  O: (2, 1), (3, 1)
+        play(&mut controller, &[(1, 1), (2, 1), (2, 2), (3, 1), (3, 3)]);
+        assert_eq!(sorted_line(&controller), vec![(1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn winning_line_on_bigger_playground() {
+        let mut controller = Controller::with_size(7, 5, 4);
+        // X: (5, 1), (4, 2), (3, 3), (2, 4)  O: (1, 1), (1, 2), (1, 3)
+        play(
+            &mut controller,
+            &[(5, 1), (1, 1), (4, 2), (1, 2), (3, 3), (1, 3), (2, 4)],
+        );
+        assert_eq!(
+            sorted_line(&controller),
+            vec![(2, 4), (3, 3), (4, 2), (5, 1)]
+        );
+    }
+
+    #[test]
+    fn winning_line_has_win_length_cells() {
+        let mut controller = Controller::with_size(7, 5, 4);
+        // X: (1, 5) to (5, 5) with the last one in the middle  O: (1, 1) to (4, 1)
+        play(
+            &mut controller,
+            &[
+                (1, 5),
+                (1, 1),
+                (2, 5),
+                (2, 1),
+                (4, 5),
+                (3, 1),
+                (5, 5),
+                (5, 1),
+                (3, 5),
+            ],
+        );
+        let line = sorted_line(&controller);
+        assert_eq!(line, vec![(1, 5), (2, 5), (3, 5), (4, 5)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "playground is too big")]
+    fn refuses_playground_bigger_than_position() {
+        Controller::with_size(17, 17, 5);
     }
 }
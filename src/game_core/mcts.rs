@@ -0,0 +1,300 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::rand::Rng;
+use crate::game_core::{Budget, Cell, Move, Player, Position};
+use std::time::Instant;
+
+/// How much the tree search prefers the moves it visited less
+const EXPLORATION: f64 = 1.4;
+
+/// A move of the search tree and what the playouts through it gave
+struct Node {
+    /// index of the cell played to reach the node
+    index: usize,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    /// cells not tried yet, None until the node is visited again after it is added
+    untried: Option<Vec<usize>>,
+    visits: u32,
+    /// sum of the results for the player who played index, 1 for a win and 0.5 for a draw
+    score: f64,
+    /// result for the player who played index if the game is over after it
+    end: Option<f64>,
+}
+impl Node {
+    fn new(index: usize, parent: Option<usize>, end: Option<f64>) -> Node {
+        Node {
+            index,
+            parent,
+            children: Vec::new(),
+            untried: None,
+            visits: 0,
+            score: 0.0,
+            end,
+        }
+    }
+}
+
+/// Computer opponent that plays with Monte Carlo tree search (UCT)
+///
+/// It plays random games from the searched moves and keeps trying the moves
+/// that won most of them, so it stays strong on playgrounds like 7x7 and 15x15
+/// that are too big for Search. Only the cells next to filled ones are tried,
+/// winning moves are always played and a line of the opponent is always blocked.
+pub struct MctsPlayer {
+    budget: Budget,
+    rng: Rng,
+    nodes: Vec<Node>,
+}
+impl Default for MctsPlayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MctsPlayer {
+    /// Creates new MctsPlayer that searches 10000 iterations for each move
+    pub fn new() -> MctsPlayer {
+        MctsPlayer {
+            budget: Budget::Iterations(10_000),
+            rng: Rng::from_time(),
+            nodes: Vec::new(),
+        }
+    }
+    /// Sets how long the player searches for each move
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+    /// Restarts the random games from seed so the same moves can be played again
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+    /// returns how long the player searches for each move
+    pub fn get_budget(&self) -> Budget {
+        self.budget
+    }
+
+    /// Returns the index of the best move for the player whose turn it is
+    /// or None if there is no free cell
+    fn search(&mut self, position: &Position) -> Option<usize> {
+        if position.is_full() {
+            return None;
+        }
+        self.nodes.clear();
+        self.nodes.push(Node::new(0, None, None));
+        let start = Instant::now();
+        let mut iterations = 0;
+        loop {
+            match self.budget {
+                Budget::Iterations(limit) if iterations >= limit.max(1) => break,
+                Budget::Time(limit) if iterations > 0 && start.elapsed() >= limit => break,
+                _ => {}
+            }
+            self.iterate(position);
+            iterations += 1;
+        }
+        let best = self.nodes[0]
+            .children
+            .iter()
+            .copied()
+            .max_by_key(|&child| self.nodes[child].visits)?;
+        Some(self.nodes[best].index)
+    }
+    /// Walks down the tree, adds one node, plays a random game from it
+    /// and adds its result to the nodes on the way
+    fn iterate(&mut self, root: &Position) {
+        let mut position = root.clone();
+        let mut node = 0;
+        while self.nodes[node].end.is_none() {
+            if self.nodes[node].untried.is_none() {
+                self.nodes[node].untried = Some(moves(&position));
+            }
+            let untried = self.nodes[node].untried.as_mut().unwrap();
+            if !untried.is_empty() {
+                let choice = self.rng.generate_range(0, untried.len() as u64) as usize;
+                let index = untried.swap_remove(choice);
+                let player = position.get_player_turn();
+                position.play(index);
+                let end = if position.is_win_through(index, player) {
+                    Some(1.0)
+                } else if position.is_full() {
+                    Some(0.5)
+                } else {
+                    None
+                };
+                self.nodes.push(Node::new(index, Some(node), end));
+                let child = self.nodes.len() - 1;
+                self.nodes[node].children.push(child);
+                node = child;
+                break;
+            }
+            node = self.select(node);
+            position.play(self.nodes[node].index);
+        }
+        let mut result = match self.nodes[node].end {
+            Some(result) => result,
+            None => {
+                let player = position.get_player_turn();
+                match self.playout(&mut position) {
+                    Cell::Empty => 0.5,
+                    winner if winner == player => 0.0,
+                    _ => 1.0,
+                }
+            }
+        };
+        let mut next = Some(node);
+        while let Some(node) = next {
+            self.nodes[node].visits += 1;
+            self.nodes[node].score += result;
+            result = 1.0 - result;
+            next = self.nodes[node].parent;
+        }
+    }
+    /// Returns the child of node with the best upper confidence bound
+    fn select(&self, node: usize) -> usize {
+        let log_visits = f64::from(self.nodes[node].visits).ln();
+        let bound = |child: usize| {
+            let child = &self.nodes[child];
+            let visits = f64::from(child.visits);
+            child.score / visits + EXPLORATION * (log_visits / visits).sqrt()
+        };
+        let children = &self.nodes[node].children;
+        let mut best = children[0];
+        for &child in children[1..].iter() {
+            if bound(child) > bound(best) {
+                best = child;
+            }
+        }
+        best
+    }
+    /// Plays random moves until the game is over and returns the winner
+    /// or Cell::Empty for a draw
+    fn playout(&mut self, position: &mut Position) -> Cell {
+        let mut free: Vec<usize> = position.get_free_cells().indexes().collect();
+        while !free.is_empty() {
+            let choice = self.rng.generate_range(0, free.len() as u64) as usize;
+            let index = free.swap_remove(choice);
+            let player = position.get_player_turn();
+            position.play(index);
+            if position.is_win_through(index, player) {
+                return player;
+            }
+        }
+        Cell::Empty
+    }
+}
+
+impl Player for MctsPlayer {
+    /// Returns the move with the most visits after the search
+    fn choose_move(&mut self, position: &Position) -> Move {
+        let (row, column) = match self.search(position) {
+            Some(index) => position.location(index),
+            None => (0, 0),
+        };
+        Move::new(position.get_player_turn(), row, column)
+    }
+}
+
+/// Returns the cells worth trying: a winning cell if there is one, otherwise
+/// the cells that block the opponent lines, otherwise the cells next to filled ones
+fn moves(position: &Position) -> Vec<usize> {
+    let player = position.get_player_turn();
+    let opponent = player.opponent();
+    let near = near_cells(position);
+    if let Some(&index) = near
+        .iter()
+        .find(|&&index| position.is_winning_index(index, player))
+    {
+        return vec![index];
+    }
+    let threats: Vec<usize> = near
+        .iter()
+        .copied()
+        .filter(|&index| position.is_winning_index(index, opponent))
+        .collect();
+    if threats.is_empty() {
+        near
+    } else {
+        threats
+    }
+}
+/// Returns the free cells next to a filled one or the center cell of an empty playground
+///
+/// A winning cell is always next to a cell of its line, so no win or block is left out.
+fn near_cells(position: &Position) -> Vec<usize> {
+    let (width, height) = (position.get_width(), position.get_height());
+    let free = position.get_free_cells();
+    if free.count() == width * height {
+        return vec![position.index(width.div_ceil(2), height.div_ceil(2))];
+    }
+    free.indexes()
+        .filter(|&index| {
+            let (row, column) = position.location(index);
+            (row.saturating_sub(1)..=row + 1).any(|near_row| {
+                (column.saturating_sub(1)..=column + 1)
+                    .any(|near_column| position.get_content(near_row, near_column) != Cell::Empty)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a 7x7 position where 4 cells in a line win
+    /// after X and O play the (row, column) locations in turn
+    fn position(moves: &[(usize, usize)]) -> Position {
+        let mut position = Position::new(7, 7, 4);
+        for &(row, column) in moves.iter() {
+            position.play(position.index(row, column));
+        }
+        position
+    }
+
+    fn mcts_player() -> MctsPlayer {
+        let mut player = MctsPlayer::new();
+        player.set_seed(7);
+        player.set_budget(Budget::Iterations(2_000));
+        player
+    }
+
+    #[test]
+    fn takes_immediate_win() {
+        // X: (2, 4), (3, 4), (4, 4)  O: (2, 2), (3, 2), (6, 6)
+        let position = position(&[(2, 4), (2, 2), (3, 4), (3, 2), (4, 4), (6, 6)]);
+        let m = mcts_player().choose_move(&position);
+        assert!(m.column == 4 && (m.row == 1 || m.row == 5));
+    }
+
+    #[test]
+    fn blocks_opponent_line() {
+        // X: (2, 4), (3, 4), (4, 4)  O: (6, 6), (1, 4)
+        let position = position(&[(2, 4), (6, 6), (3, 4), (1, 4), (4, 4)]);
+        let m = mcts_player().choose_move(&position);
+        assert_eq!((m.row, m.column), (5, 4));
+    }
+
+    #[test]
+    fn same_seed_plays_same_move() {
+        let position = position(&[(4, 4), (4, 3)]);
+        let first = mcts_player().choose_move(&position);
+        let second = mcts_player().choose_move(&position);
+        assert_eq!((first.row, first.column), (second.row, second.column));
+    }
+
+    #[test]
+    fn zero_iterations_still_play() {
+        let mut player = mcts_player();
+        player.set_budget(Budget::Iterations(0));
+        let m = player.choose_move(&position(&[]));
+        assert_eq!((m.row, m.column), (4, 4));
+        let m = player.choose_move(&position(&[(4, 4)]));
+        assert!(m.row != 0 && m.column != 0);
+    }
+}
@@ -8,11 +8,24 @@ file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
 mod computer_player;
 mod controller;
+mod mcts;
+pub mod notation;
+mod player;
+mod position;
+pub mod rand;
+mod search;
 
 pub use computer_player::*;
 pub use controller::*;
+pub use mcts::*;
+pub use player::*;
+pub use position::*;
+pub use search::*;
 
-#[derive(Clone, Copy, PartialEq)]
+use std::time::Duration;
+
+/// Content of a playground cell, also used to name the players
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Cell {
     X,
     O,
@@ -20,35 +33,94 @@ pub enum Cell {
 }
 impl Cell {
     /// returns the Cell contents as &str
-    pub fn to_str(&self) -> &str {
+    pub fn to_str(self) -> &'static str {
         match self {
             Cell::X => "X",
             Cell::O => "O",
             Cell::Empty => "",
         }
     }
+    /// returns the other player, Empty has no other player and stays Empty
+    pub fn opponent(self) -> Cell {
+        match self {
+            Cell::X => Cell::O,
+            Cell::O => Cell::X,
+            Cell::Empty => Cell::Empty,
+        }
+    }
+}
+
+/// A player putting its mark in the cell with row and column location
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Move {
+    pub player: Cell,
+    pub row: usize,
+    pub column: usize,
+}
+impl Move {
+    /// Creates new Move and returns it
+    pub fn new(player: Cell, row: usize, column: usize) -> Move {
+        Move {
+            player,
+            row,
+            column,
+        }
+    }
+}
+
+/// The state of the game
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameStatus {
+    /// There are free cells and no winner yet
+    InProgress,
+    /// player has filled the (row, column) locations of line
+    Win {
+        player: Cell,
+        line: Vec<(usize, usize)>,
+    },
+    /// All cells are filled with no winner
+    Draw,
+    /// player has given up and the opponent wins
+    Resigned { player: Cell },
 }
 
-#[derive(Clone, Copy, PartialEq)]
+/// The result of a game for a player or of perfect play for the player to move
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+/// How long MctsPlayer searches for each move
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Budget {
+    /// Stops after this number of random games, at least one is played
+    Iterations(u32),
+    /// Stops when this time is over, at least one random game is played
+    Time(Duration),
+}
+
+/// How ComputerPlayer chooses its moves
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Difficulty {
+    /// most plies searched from the position, None searches the whole game tree
+    pub depth: Option<usize>,
+    /// probability of choosing a move that is worse than the best one found
+    pub blunder: f64,
+    /// whether a line the opponent completes next move is always blocked
+    pub block: bool,
+}
+
+/// Named presets of Difficulty
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum Level {
+    /// Plays random moves
     Stupid,
+    /// Completes its lines and blocks the opponent lines
     Normal,
+    /// Also sees the forks a few moves ahead and rarely misses the best move
     Smart,
-}
-
-pub mod rand {
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    pub fn generate() -> u64 {
-        let mut seed = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
-        seed = seed.wrapping_add(0xa0761d6478bd642f);
-        let t: u128 = (seed).wrapping_mul(seed ^ 0xe7037ed1a0b428db);
-        ((t >> 64) ^ t) as u64
-    }
-    pub fn generate_range(low: u64, high: u64) -> u64 {
-        generate() % (high - low) + low
-    }
+    /// Searches the whole game tree of small playgrounds and never loses on them
+    Perfect,
 }
@@ -0,0 +1,293 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+//! Text forms of playground locations shared by everything that reads or writes them.
+//!
+//! A location can be written as:
+//! - a cell number counting the cells line by line from 1, like `5`,
+//! - the letters shown over the playground followed by the number shown on
+//!   its left, like `b2`, where `a` is the leftmost cell and `1` the top line,
+//! - a `line,cell` pair of numbers as they are seen on the playground, like
+//!   `3,2` for `b3`: the line number on the left, then the cell in the line.
+//!
+//! A saved game is the playground size, the first player and the played
+//! locations in order:
+//!
+//! ```text
+//! size = 3x3
+//! win_length = 3
+//! first = x
+//! moves = b2 a1 c3
+//! ```
+//!
+//! Games saved without `first` start with X.
+
+use crate::game_core::{Cell, Controller, Move, MoveError, MAX_CELLS};
+use std::error::Error;
+use std::fmt;
+
+/// Returns the 1-based (row, column) location written in text for a `width` x `height`
+/// playground or None if text is not a location inside it
+pub fn parse_location(text: &str, width: usize, height: usize) -> Option<(usize, usize)> {
+    let text = text.trim().to_lowercase();
+    let (row, column) = if let Some((line, cell)) = text.split_once(',') {
+        (cell.trim().parse().ok()?, line.trim().parse().ok()?)
+    } else if text.starts_with(|c: char| c.is_ascii_lowercase()) {
+        let digits = text.find(|c: char| !c.is_ascii_lowercase())?;
+        (
+            parse_letters(&text[..digits])?,
+            text[digits..].parse().ok()?,
+        )
+    } else {
+        let number: usize = text.parse().ok()?;
+        if number == 0 {
+            return None;
+        }
+        ((number - 1) % width + 1, (number - 1) / width + 1)
+    };
+    if row < 1 || column < 1 || row > width || column > height {
+        return None;
+    }
+    Some((row, column))
+}
+/// Returns the location written as letters for the row and the column number, like `b2`
+pub fn format_location(row: usize, column: usize) -> String {
+    format!("{}{}", row_letters(row), column)
+}
+/// Returns the letters of the 1-based row: a to z, then aa, ab and so on
+pub fn row_letters(row: usize) -> String {
+    let mut letters = Vec::new();
+    let mut row = row;
+    while row > 0 {
+        row -= 1;
+        letters.push((b'a' + (row % 26) as u8) as char);
+        row /= 26;
+    }
+    letters.iter().rev().collect()
+}
+/// Returns the 1-based row of letters written by row_letters
+fn parse_letters(letters: &str) -> Option<usize> {
+    if letters.is_empty() || letters.len() > 4 {
+        return None;
+    }
+    Some(
+        letters
+            .bytes()
+            .fold(0, |row, letter| row * 26 + (letter - b'a') as usize + 1),
+    )
+}
+
+/// Returns the text of the saved game
+pub fn write_game(c: &Controller) -> String {
+    let moves: Vec<String> = c
+        .get_history()
+        .iter()
+        .map(|&(row, column)| format_location(row, column))
+        .collect();
+    format!(
+        "size = {}x{}\nwin_length = {}\nfirst = {}\nmoves = {}\n",
+        c.get_width(),
+        c.get_height(),
+        c.get_win_length(),
+        c.get_first_player().to_str().to_lowercase(),
+        moves.join(" ")
+    )
+}
+/// The reasons for refusing a saved game
+#[derive(Clone, PartialEq, Debug)]
+pub enum ReadError {
+    /// The size is missing or is not like 3x3
+    Size,
+    /// The win length is missing or is not a number
+    WinLength,
+    /// The win length is 0 or longer than the playground
+    SizeWinLength,
+    /// The playground has more than MAX_CELLS cells
+    TooBig,
+    /// The first player is not x or o
+    FirstPlayer,
+    /// The moves line is missing, like in a cut file
+    Moves,
+    /// This text of the moves is not a location
+    Location(String),
+    /// The move at this location cannot be played
+    Move(String, MoveError),
+}
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Size => f.write_str("The size is missing or wrong!"),
+            ReadError::WinLength => f.write_str("The win length is missing or wrong!"),
+            ReadError::SizeWinLength => f.write_str("The size does not fit the win length!"),
+            ReadError::TooBig => f.write_str("The playground is too big!"),
+            ReadError::FirstPlayer => f.write_str("The first player is wrong!"),
+            ReadError::Moves => f.write_str("The moves are missing!"),
+            ReadError::Location(location) => write!(f, "{} is not a location!", location),
+            ReadError::Move(location, error) => write!(f, "{}: {}", location, error),
+        }
+    }
+}
+impl Error for ReadError {}
+
+/// Returns the game saved in text by replaying its moves or the reason it is refused
+pub fn read_game(text: &str) -> Result<Controller, ReadError> {
+    let (mut size, mut win_length, mut moves) = (None, None, None);
+    let mut first_player = Some(Cell::X);
+    for line in text.lines() {
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+        match key {
+            "size" => size = value.split_once('x'),
+            "win_length" => win_length = value.parse::<usize>().ok(),
+            "first" => {
+                first_player = match value {
+                    "x" | "X" => Some(Cell::X),
+                    "o" | "O" => Some(Cell::O),
+                    _ => None,
+                }
+            }
+            "moves" => moves = Some(value),
+            _ => {}
+        }
+    }
+    let (width, height) = match size.map(|(w, h)| (w.parse::<usize>(), h.parse::<usize>())) {
+        Some((Ok(width), Ok(height))) => (width, height),
+        _ => return Err(ReadError::Size),
+    };
+    let win_length = win_length.ok_or(ReadError::WinLength)?;
+    if width == 0 || height == 0 || win_length == 0 || win_length > width.max(height) {
+        return Err(ReadError::SizeWinLength);
+    }
+    if width.saturating_mul(height) > MAX_CELLS {
+        return Err(ReadError::TooBig);
+    }
+    let first_player = first_player.ok_or(ReadError::FirstPlayer)?;
+    // the moves line comes last, so a cut file misses it
+    let moves = moves.ok_or(ReadError::Moves)?;
+    let mut controller = Controller::with_size(width, height, win_length);
+    controller.set_first_player(first_player);
+    for location in moves.split_whitespace() {
+        let (row, column) = parse_location(location, width, height)
+            .ok_or_else(|| ReadError::Location(location.to_string()))?;
+        let player = controller.get_player_turn();
+        if let Err(error) = controller.try_play(Move::new(player, row, column)) {
+            return Err(ReadError::Move(location.to_string(), error));
+        }
+    }
+    Ok(controller)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_letters_and_column() {
+        assert_eq!(parse_location("b2", 3, 3), Some((2, 2)));
+        assert_eq!(parse_location(" C1 ", 3, 3), Some((3, 1)));
+        assert_eq!(parse_location("aa3", 30, 3), Some((27, 3)));
+    }
+
+    #[test]
+    fn parses_line_cell_pair_as_seen_on_the_playground() {
+        assert_eq!(parse_location("3,2", 3, 3), parse_location("b3", 3, 3));
+        assert_eq!(parse_location("3,1", 3, 3), Some((1, 3)));
+        assert_eq!(parse_location("1, 4", 4, 3), Some((4, 1)));
+    }
+
+    #[test]
+    fn parses_cell_numbers_line_by_line() {
+        assert_eq!(parse_location("1", 3, 3), Some((1, 1)));
+        assert_eq!(parse_location("6", 3, 3), Some((3, 2)));
+        assert_eq!(parse_location("5", 4, 3), Some((1, 2)));
+    }
+
+    #[test]
+    fn refuses_locations_out_of_range() {
+        for text in ["d1", "a4", "0", "10", "0,1", "4,1", "1,4"].iter() {
+            assert_eq!(parse_location(text, 3, 3), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn refuses_garbage() {
+        for text in ["", "b", "2b", "b-2", "x,y", "1,2,3", "abcde1", "#"].iter() {
+            assert_eq!(parse_location(text, 3, 3), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn formats_locations() {
+        assert_eq!(format_location(2, 3), "b3");
+        assert_eq!(row_letters(26), "z");
+        assert_eq!(row_letters(27), "aa");
+        assert_eq!(
+            parse_location(&format_location(28, 2), 30, 3),
+            Some((28, 2))
+        );
+    }
+
+    #[test]
+    fn saved_game_reads_back() {
+        let mut controller = Controller::with_size(5, 4, 3);
+        controller.set_first_player(Cell::O);
+        for &(row, column) in [(2, 3), (1, 1), (5, 4)].iter() {
+            let player = controller.get_player_turn();
+            controller.try_play(Move::new(player, row, column)).unwrap();
+        }
+        let text = write_game(&controller);
+        assert_eq!(
+            text,
+            "size = 5x4\nwin_length = 3\nfirst = o\nmoves = b3 a1 e4\n"
+        );
+        let loaded = read_game(&text).unwrap();
+        assert_eq!(
+            (
+                loaded.get_width(),
+                loaded.get_height(),
+                loaded.get_win_length()
+            ),
+            (5, 4, 3)
+        );
+        assert_eq!(loaded.get_first_player(), Cell::O);
+        assert_eq!(loaded.get_history(), controller.get_history());
+        assert_eq!(loaded.get_player_turn(), Cell::X);
+    }
+
+    #[test]
+    fn refuses_truncated_games() {
+        let text = "size = 3x3\nwin_length = 3\nfirst = x\nmoves = b2 a1\n";
+        assert!(read_game(text).is_ok());
+        for end in [0, 10, 25, 36].iter() {
+            assert!(read_game(&text[..*end]).is_err(), "{:?}", &text[..*end]);
+        }
+        assert_eq!(
+            read_game("size = 3x3\nwin_length = 3\nmoves = b2 a").err(),
+            Some(ReadError::Location("a".to_string()))
+        );
+        assert_eq!(
+            read_game("size = 3x3\nwin_length = 3\nmoves = b2 b2").err(),
+            Some(ReadError::Move("b2".to_string(), MoveError::Occupied))
+        );
+    }
+
+    #[test]
+    fn refuses_oversized_games() {
+        assert_eq!(
+            read_game("size = 17x17\nwin_length = 5\nmoves =\n").err(),
+            Some(ReadError::TooBig)
+        );
+        assert_eq!(
+            read_game("size = 3x3\nwin_length = 4\nmoves =\n").err(),
+            Some(ReadError::SizeWinLength)
+        );
+        assert!(read_game("size = 16x16\nwin_length = 5\nmoves =\n").is_ok());
+    }
+}
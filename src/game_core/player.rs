@@ -0,0 +1,208 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Cell, Controller, GameStatus, Move, MoveError, Position};
+use std::collections::VecDeque;
+
+/// What a player wants to do in its turn
+#[derive(Clone, Debug)]
+pub enum Action {
+    /// Plays the move
+    Play(Move),
+    /// Takes back this number of moves
+    Undo(usize),
+    /// Plays this number of undone moves again
+    Redo(usize),
+    /// Replaces the game with a loaded one
+    Load(Controller),
+    /// Gives up and lets the opponent win
+    Resign,
+    /// Starts the game again
+    New,
+    /// Stops the game before it is over
+    Quit,
+}
+
+/// A participant of the game: a human, a computer or anything that can choose moves
+pub trait Player {
+    /// Returns the move to play in the position for the player whose turn it is
+    fn choose_move(&mut self, position: &Position) -> Move;
+    /// Returns what to do in the turn, players that only play moves keep the default
+    fn choose_action(&mut self, controller: &Controller) -> Action {
+        Action::Play(self.choose_move(&Position::from(controller)))
+    }
+    /// Called when the controller refuses the move, the player is asked again after it
+    fn move_rejected(&mut self, _m: Move, _error: MoveError) {}
+}
+
+/// Player that plays a fixed list of (row, column) locations and resigns when the list is over
+///
+/// Refused moves are skipped and the game goes on with the next one.
+pub struct ScriptedPlayer {
+    moves: VecDeque<(usize, usize)>,
+}
+impl ScriptedPlayer {
+    /// Creates new ScriptedPlayer that plays moves in order and returns it
+    pub fn new(moves: Vec<(usize, usize)>) -> ScriptedPlayer {
+        ScriptedPlayer {
+            moves: moves.into(),
+        }
+    }
+}
+impl Player for ScriptedPlayer {
+    /// Returns the next move of the list or the (0, 0) location outside the
+    /// playground, which is always refused, if the list is over
+    fn choose_move(&mut self, position: &Position) -> Move {
+        let (row, column) = self.moves.pop_front().unwrap_or((0, 0));
+        Move::new(position.get_player_turn(), row, column)
+    }
+    fn choose_action(&mut self, controller: &Controller) -> Action {
+        match self.moves.pop_front() {
+            Some((row, column)) => {
+                Action::Play(Move::new(controller.get_player_turn(), row, column))
+            }
+            None => Action::Resign,
+        }
+    }
+}
+
+impl GameStatus {
+    /// Returns the player who won, also by the resignation of the opponent,
+    /// or Cell::Empty if there is not any
+    pub fn get_winner(&self) -> Cell {
+        match self {
+            GameStatus::Win { player, .. } => *player,
+            GameStatus::Resigned { player: Cell::X } => Cell::O,
+            GameStatus::Resigned { player: Cell::O } => Cell::X,
+            _ => Cell::Empty,
+        }
+    }
+}
+
+/// Lets x and o play until the game is over and returns its status
+///
+/// The status is GameStatus::InProgress if one of the players quits and
+/// GameStatus::Resigned if one of them resigns.
+/// Undo and redo actions that cannot be done completely are ignored.
+pub fn play_game(
+    controller: &mut Controller,
+    x: &mut dyn Player,
+    o: &mut dyn Player,
+) -> GameStatus {
+    loop {
+        let status = controller.status();
+        if status != GameStatus::InProgress {
+            return status;
+        }
+        let player: &mut dyn Player = match controller.get_player_turn() {
+            Cell::X => &mut *x,
+            _ => &mut *o,
+        };
+        match player.choose_action(controller) {
+            Action::Play(m) => {
+                if let Err(error) = controller.try_play(m) {
+                    player.move_rejected(m, error);
+                }
+            }
+            Action::Undo(steps) => {
+                if controller.get_history().len() >= steps {
+                    for _ in 0..steps {
+                        controller.undo();
+                    }
+                }
+            }
+            Action::Redo(steps) => {
+                if controller.get_redo_count() >= steps {
+                    for _ in 0..steps {
+                        controller.redo();
+                    }
+                }
+            }
+            Action::Load(loaded) => *controller = loaded,
+            Action::Resign => {
+                return GameStatus::Resigned {
+                    player: controller.get_player_turn(),
+                }
+            }
+            Action::New => controller.initialize(),
+            Action::Quit => return GameStatus::InProgress,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Player that always plays the first free cell
+    struct FirstFreePlayer;
+    impl Player for FirstFreePlayer {
+        fn choose_move(&mut self, position: &Position) -> Move {
+            let index = position.get_free_cells().indexes().next().unwrap();
+            let (row, column) = position.location(index);
+            Move::new(position.get_player_turn(), row, column)
+        }
+    }
+
+    #[test]
+    fn scripted_game_ends_in_win() {
+        let mut controller = Controller::new();
+        let mut x = ScriptedPlayer::new(vec![(1, 1), (2, 1), (3, 1)]);
+        let mut o = ScriptedPlayer::new(vec![(1, 2), (2, 2)]);
+        let status = play_game(&mut controller, &mut x, &mut o);
+        assert_eq!(
+            status,
+            GameStatus::Win {
+                player: Cell::X,
+                line: vec![(1, 1), (2, 1), (3, 1)],
+            }
+        );
+    }
+
+    #[test]
+    fn scripted_game_ends_in_draw() {
+        let mut controller = Controller::new();
+        let mut x = ScriptedPlayer::new(vec![(1, 1), (3, 1), (2, 3), (1, 2), (3, 3)]);
+        let mut o = ScriptedPlayer::new(vec![(2, 1), (2, 2), (3, 2), (1, 3)]);
+        let status = play_game(&mut controller, &mut x, &mut o);
+        assert_eq!(status, GameStatus::Draw);
+    }
+
+    #[test]
+    fn refused_move_is_skipped_and_resigns_when_script_is_over() {
+        let mut controller = Controller::new();
+        let mut x = ScriptedPlayer::new(vec![(2, 2)]);
+        let mut o = ScriptedPlayer::new(vec![(2, 2), (1, 1)]);
+        let status = play_game(&mut controller, &mut x, &mut o);
+        assert_eq!(controller.get_history(), &[(2, 2), (1, 1)]);
+        assert_eq!(status, GameStatus::Resigned { player: Cell::X });
+        assert_eq!(status.get_winner(), Cell::O);
+    }
+
+    #[test]
+    fn choose_move_is_refused_when_script_is_over() {
+        let mut controller = Controller::new();
+        let m = ScriptedPlayer::new(Vec::new()).choose_move(&Position::from(&controller));
+        assert_eq!(controller.try_play(m), Err(MoveError::OutOfBounds));
+    }
+
+    #[test]
+    fn custom_player_plays_scripted_one() {
+        let mut controller = Controller::new();
+        let mut o = ScriptedPlayer::new(vec![(1, 2), (2, 2)]);
+        let status = play_game(&mut controller, &mut FirstFreePlayer, &mut o);
+        assert_eq!(controller.get_winner(), Cell::X);
+        assert!(matches!(
+            status,
+            GameStatus::Win {
+                player: Cell::X,
+                ..
+            }
+        ));
+    }
+}
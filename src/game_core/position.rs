@@ -0,0 +1,403 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Cell, Controller};
+use std::ops::{BitAnd, BitOr, Not};
+use std::sync::{Arc, Mutex};
+
+/// The biggest number of cells a Position can hold
+pub const MAX_CELLS: usize = 256;
+
+/// Fixed size set of cell indexes, one bit for each cell
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+pub struct Bitboard([u64; 4]);
+impl Bitboard {
+    /// Returns true if the cell index is in the set
+    pub fn get(&self, index: usize) -> bool {
+        self.0[index / 64] & (1 << (index % 64)) != 0
+    }
+    /// Adds the cell index to the set
+    pub fn set(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+    /// Removes the cell index from the set
+    pub fn clear(&mut self, index: usize) {
+        self.0[index / 64] &= !(1 << (index % 64));
+    }
+    /// Returns true if there is no index in the set
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+    /// Returns how many indexes are in the set
+    pub fn count(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+    /// Returns true if all indexes of other are in the set
+    pub fn contains(&self, other: Bitboard) -> bool {
+        *self & other == other
+    }
+    /// Returns the indexes of the set from the lowest to the highest
+    pub fn indexes(self) -> impl Iterator<Item = usize> {
+        (0..4).flat_map(move |i| {
+            let mut word = self.0[i];
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(i * 64 + bit)
+            })
+        })
+    }
+}
+impl BitAnd for Bitboard {
+    type Output = Bitboard;
+    fn bitand(self, other: Bitboard) -> Bitboard {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(other.0.iter()) {
+            *word &= other;
+        }
+        Bitboard(words)
+    }
+}
+impl BitOr for Bitboard {
+    type Output = Bitboard;
+    fn bitor(self, other: Bitboard) -> Bitboard {
+        let mut words = self.0;
+        for (word, other) in words.iter_mut().zip(other.0.iter()) {
+            *word |= other;
+        }
+        Bitboard(words)
+    }
+}
+impl Not for Bitboard {
+    type Output = Bitboard;
+    fn not(self) -> Bitboard {
+        let mut words = self.0;
+        for word in words.iter_mut() {
+            *word = !*word;
+        }
+        Bitboard(words)
+    }
+}
+
+/// Tables of every playground size used so far
+static TABLES: Mutex<Vec<Arc<Tables>>> = Mutex::new(Vec::new());
+
+/// Precomputed data shared by all positions with the same playground size
+struct Tables {
+    width: usize,
+    height: usize,
+    win_length: usize,
+    /// All cells of the playground
+    full: Bitboard,
+    /// Every win_length cells in a line
+    win_masks: Vec<Bitboard>,
+    /// Indexes of the win masks that contain each cell
+    masks_of_cell: Vec<Vec<usize>>,
+}
+impl Tables {
+    fn new(width: usize, height: usize, win_length: usize) -> Tables {
+        assert!(width * height <= MAX_CELLS, "playground is too big");
+        let mut full = Bitboard::default();
+        for index in 0..width * height {
+            full.set(index);
+        }
+        let mut win_masks = Vec::new();
+        let mut masks_of_cell = vec![Vec::new(); width * height];
+        let directions: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+        for y in 0..height as isize {
+            for x in 0..width as isize {
+                for &(dx, dy) in directions.iter() {
+                    let end_x = x + dx * (win_length as isize - 1);
+                    let end_y = y + dy * (win_length as isize - 1);
+                    if end_x < 0 || end_y < 0 || end_x >= width as isize || end_y >= height as isize
+                    {
+                        continue;
+                    }
+                    let mut mask = Bitboard::default();
+                    for i in 0..win_length as isize {
+                        let index = (y + i * dy) as usize * width + (x + i * dx) as usize;
+                        mask.set(index);
+                        masks_of_cell[index].push(win_masks.len());
+                    }
+                    win_masks.push(mask);
+                }
+            }
+        }
+        Tables {
+            width,
+            height,
+            win_length,
+            full,
+            win_masks,
+            masks_of_cell,
+        }
+    }
+    /// Returns the tables of the playground size, they are only built the first time
+    fn shared(width: usize, height: usize, win_length: usize) -> Arc<Tables> {
+        let mut tables = TABLES.lock().unwrap_or_else(|error| error.into_inner());
+        if let Some(found) = tables.iter().find(|found| {
+            (found.width, found.height, found.win_length) == (width, height, win_length)
+        }) {
+            return Arc::clone(found);
+        }
+        let new = Arc::new(Tables::new(width, height, win_length));
+        tables.push(Arc::clone(&new));
+        new
+    }
+}
+
+/// Compact copy of the playground with one bitmask for each player
+///
+/// Cells are addressed either by the 1-based (row, column) locations of
+/// Controller or by 0-based indexes, where the index of a location is
+/// `(column - 1) * width + (row - 1)`.
+#[derive(Clone)]
+pub struct Position {
+    x: Bitboard,
+    o: Bitboard,
+    player_turn: Cell,
+    tables: Arc<Tables>,
+}
+impl Position {
+    /// Creates new empty Position with a `width` x `height` playground where
+    /// `win_length` cells in a line are needed to win
+    ///
+    /// Panics if the playground has more than MAX_CELLS cells.
+    pub fn new(width: usize, height: usize, win_length: usize) -> Position {
+        Position {
+            x: Bitboard::default(),
+            o: Bitboard::default(),
+            player_turn: Cell::X,
+            tables: Tables::shared(width, height, win_length),
+        }
+    }
+    /// returns the number of cells in each line of the playground
+    pub fn get_width(&self) -> usize {
+        self.tables.width
+    }
+    /// returns the number of lines of the playground
+    pub fn get_height(&self) -> usize {
+        self.tables.height
+    }
+    /// returns how many cells in a line are needed to win
+    pub fn get_win_length(&self) -> usize {
+        self.tables.win_length
+    }
+    /// returns which player will play next
+    pub fn get_player_turn(&self) -> Cell {
+        self.player_turn
+    }
+    /// returns the cells of player
+    pub fn get_cells(&self, player: Cell) -> Bitboard {
+        match player {
+            Cell::X => self.x,
+            Cell::O => self.o,
+            Cell::Empty => self.get_free_cells(),
+        }
+    }
+    /// returns the cells that are not filled yet
+    pub fn get_free_cells(&self) -> Bitboard {
+        self.tables.full & !(self.x | self.o)
+    }
+    /// Returns the index of the location
+    pub fn index(&self, row: usize, column: usize) -> usize {
+        (column - 1) * self.tables.width + (row - 1)
+    }
+    /// Returns the (row, column) location of the index
+    pub fn location(&self, index: usize) -> (usize, usize) {
+        (index % self.tables.width + 1, index / self.tables.width + 1)
+    }
+    /// Takes row and column parameters and returns the content of the cell with this location
+    pub fn get_content(&self, row: usize, column: usize) -> Cell {
+        if row < 1 || column < 1 || row > self.tables.width || column > self.tables.height {
+            return Cell::Empty;
+        }
+        self.get_content_at(self.index(row, column))
+    }
+    /// Returns the content of the cell with this index
+    pub fn get_content_at(&self, index: usize) -> Cell {
+        if self.x.get(index) {
+            Cell::X
+        } else if self.o.get(index) {
+            Cell::O
+        } else {
+            Cell::Empty
+        }
+    }
+    /// Puts the player whose turn it is in the free cell with this index
+    pub fn play(&mut self, index: usize) {
+        match self.player_turn {
+            Cell::X => {
+                self.x.set(index);
+                self.player_turn = Cell::O;
+            }
+            Cell::O => {
+                self.o.set(index);
+                self.player_turn = Cell::X;
+            }
+            Cell::Empty => {}
+        }
+    }
+    /// Takes back the last move which was played in the cell with this index
+    pub fn unplay(&mut self, index: usize) {
+        match self.player_turn {
+            Cell::X => {
+                self.o.clear(index);
+                self.player_turn = Cell::O;
+            }
+            Cell::O => {
+                self.x.clear(index);
+                self.player_turn = Cell::X;
+            }
+            Cell::Empty => {}
+        }
+    }
+    /// Checks if there is no free cell
+    pub fn is_full(&self) -> bool {
+        self.get_free_cells().is_empty()
+    }
+    /// Checks if player has a winning line passing through the cell with this index
+    pub fn is_win_through(&self, index: usize, player: Cell) -> bool {
+        let cells = self.get_cells(player);
+        self.tables.masks_of_cell[index]
+            .iter()
+            .any(|&mask| cells.contains(self.tables.win_masks[mask]))
+    }
+    /// Checks if putting player in the free cell with this index makes a winning line
+    pub fn is_winning_index(&self, index: usize, player: Cell) -> bool {
+        let mut cells = self.get_cells(player);
+        cells.set(index);
+        self.tables.masks_of_cell[index]
+            .iter()
+            .any(|&mask| cells.contains(self.tables.win_masks[mask]))
+    }
+    /// Checks if there is a winner or no if yes it returns the winner else it returns Cell::Empty
+    pub fn get_winner(&self) -> Cell {
+        for &mask in self.tables.win_masks.iter() {
+            if self.x.contains(mask) {
+                return Cell::X;
+            }
+            if self.o.contains(mask) {
+                return Cell::O;
+            }
+        }
+        Cell::Empty
+    }
+}
+impl From<&Controller> for Position {
+    fn from(controller: &Controller) -> Position {
+        let mut position = Position::new(
+            controller.get_width(),
+            controller.get_height(),
+            controller.get_win_length(),
+        );
+        for column in 1..=controller.get_height() {
+            for row in 1..=controller.get_width() {
+                let index = position.index(row, column);
+                match controller.get_content(row, column) {
+                    Cell::X => position.x.set(index),
+                    Cell::O => position.o.set(index),
+                    Cell::Empty => {}
+                }
+            }
+        }
+        position.player_turn = controller.get_player_turn();
+        position
+    }
+}
+impl From<&Position> for Controller {
+    /// Creates Controller with the cells of the position and an empty history
+    fn from(position: &Position) -> Controller {
+        let mut controller = Controller::with_size(
+            position.get_width(),
+            position.get_height(),
+            position.get_win_length(),
+        );
+        for column in 1..=position.get_height() {
+            for row in 1..=position.get_width() {
+                controller.set_content(row, column, position.get_content(row, column));
+            }
+        }
+        controller.set_player_turn(position.get_player_turn());
+        controller
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_and_location_are_inverse() {
+        let position = Position::new(4, 3, 3);
+        for column in 1..=3 {
+            for row in 1..=4 {
+                let index = position.index(row, column);
+                assert_eq!(index, (column - 1) * 4 + (row - 1));
+                assert_eq!(position.location(index), (row, column));
+            }
+        }
+    }
+
+    #[test]
+    fn play_and_unplay_change_cells_and_turn() {
+        let mut position = Position::new(4, 3, 3);
+        assert_eq!(position.get_free_cells().count(), 12);
+        position.play(5);
+        position.play(6);
+        assert_eq!(position.get_content_at(5), Cell::X);
+        assert_eq!(position.get_content_at(6), Cell::O);
+        assert_eq!(position.get_player_turn(), Cell::X);
+        let free = position.get_free_cells();
+        assert_eq!(free.count(), 10);
+        assert!(!free.get(5) && !free.get(6) && free.get(7));
+        position.unplay(6);
+        assert_eq!(position.get_content_at(6), Cell::Empty);
+        assert_eq!(position.get_player_turn(), Cell::O);
+        position.unplay(5);
+        assert_eq!(position.get_free_cells().count(), 12);
+        assert_eq!(position.get_player_turn(), Cell::X);
+    }
+
+    #[test]
+    fn finds_winning_indexes() {
+        let mut position = Position::new(3, 3, 3);
+        // X: 0, 1  O: 4
+        for &index in [0, 4, 1].iter() {
+            position.play(index);
+        }
+        assert!(position.is_winning_index(2, Cell::X));
+        assert!(!position.is_winning_index(2, Cell::O));
+        assert!(!position.is_winning_index(8, Cell::X));
+        position.play(8);
+        position.play(2);
+        assert!(position.is_win_through(2, Cell::X));
+        assert_eq!(position.get_winner(), Cell::X);
+    }
+
+    #[test]
+    fn copies_controller() {
+        let mut controller = Controller::with_size(4, 3, 3);
+        controller.set_first_player(Cell::O);
+        controller.play_turn(2, 3);
+        let position = Position::from(&controller);
+        assert_eq!(position.get_content(2, 3), Cell::O);
+        assert_eq!(position.get_player_turn(), Cell::X);
+        assert_eq!(position.get_free_cells().count(), 11);
+    }
+
+    #[test]
+    fn positions_of_same_size_share_tables() {
+        let first = Position::new(5, 4, 3);
+        let second = Position::new(5, 4, 3);
+        assert!(Arc::ptr_eq(&first.tables, &second.tables));
+    }
+}
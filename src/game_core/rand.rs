@@ -0,0 +1,81 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// wyrand pseudo random number generator
+///
+/// The same seed always generates the same numbers, which makes games
+/// reproducible.
+#[derive(Clone)]
+pub struct Rng {
+    state: u64,
+}
+impl Rng {
+    /// Creates new Rng starting from seed and returns it
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+    /// Creates new Rng seeded from the current time and returns it
+    pub fn from_time() -> Rng {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        Rng::new(nanos as u64 ^ (nanos >> 64) as u64)
+    }
+    /// Returns the next random number
+    pub fn generate(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0xa0761d6478bd642f);
+        let t = u128::from(self.state).wrapping_mul(u128::from(self.state ^ 0xe7037ed1a0b428db));
+        ((t >> 64) ^ t) as u64
+    }
+    /// Returns the next random number in the range [low, high) or low if the range is empty
+    pub fn generate_range(&mut self, low: u64, high: u64) -> u64 {
+        if high <= low {
+            return low;
+        }
+        self.generate() % (high - low) + low
+    }
+    /// Returns the next random number in the range [0, 1)
+    pub fn generate_fraction(&mut self) -> f64 {
+        (self.generate() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_generates_same_numbers() {
+        let (mut first, mut second) = (Rng::new(42), Rng::new(42));
+        for _ in 0..100 {
+            assert_eq!(first.generate(), second.generate());
+        }
+        assert_ne!(Rng::new(1).generate(), Rng::new(2).generate());
+    }
+
+    #[test]
+    fn generates_in_range() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let number = rng.generate_range(3, 10);
+            assert!((3..10).contains(&number));
+            let fraction = rng.generate_fraction();
+            assert!((0.0..1.0).contains(&fraction));
+        }
+    }
+
+    #[test]
+    fn empty_range_generates_low() {
+        let mut rng = Rng::new(7);
+        assert_eq!(rng.generate_range(3, 3), 3);
+        assert_eq!(rng.generate_range(5, 2), 5);
+    }
+}
@@ -0,0 +1,532 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Bitboard, Cell, Move, Outcome, Position};
+use std::collections::HashMap;
+
+/// Score of winning with the next move, every extra ply takes one point off
+///
+/// A player who wins after n plies scores `WIN_SCORE - n`, the opponent
+/// scores `n - WIN_SCORE` and a draw scores 0.
+pub const WIN_SCORE: i32 = 1000;
+const INFINITY: i32 = WIN_SCORE + 1;
+/// Most positions analyze goes through before pruning, enough to settle 3x3
+/// and small playgrounds and quick on the big ones
+pub const ANALYZE_NODES: u64 = 1_000_000;
+
+#[derive(Clone, Copy)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Clone, Copy)]
+struct Entry {
+    score: i32,
+    bound: Bound,
+    /// plies searched below the position
+    depth: usize,
+}
+
+impl Outcome {
+    /// Returns the outcome of a Search score for the player it belongs to and the
+    /// number of plies, counting the moves of both players, until the game is won or lost
+    ///
+    /// Draws are only known when the playground is full, so their plies are 0.
+    pub fn from_score(score: i32) -> (Outcome, usize) {
+        if score > 0 {
+            (Outcome::Win, (WIN_SCORE - score) as usize)
+        } else if score < 0 {
+            (Outcome::Loss, (WIN_SCORE + score) as usize)
+        } else {
+            (Outcome::Draw, 0)
+        }
+    }
+}
+
+/// Negamax game tree search with alpha-beta pruning and a transposition table
+///
+/// Scores are relative to the searched position so the table entries stay
+/// valid whatever the path that reached them.
+pub struct Search {
+    /// entries by the cells of X, the cells of O and the player to move
+    table: HashMap<(Bitboard, Bitboard, Cell), Entry>,
+    /// width, height and win length of the playground the table belongs to
+    size: (usize, usize, usize),
+    /// cell indexes in the order they are tried
+    order: Vec<usize>,
+    /// most plies searched from the root, None searches the whole game tree
+    depth: Option<usize>,
+}
+impl Default for Search {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Search {
+    /// Creates new Search and returns it
+    pub fn new() -> Search {
+        Search {
+            table: HashMap::new(),
+            size: (0, 0, 0),
+            order: Vec::new(),
+            depth: None,
+        }
+    }
+    /// Sets the most plies searched from the position, None searches the whole game tree
+    ///
+    /// Positions at the limit score 0 like a draw, so the scores are only exact
+    /// for wins and losses the search can see.
+    pub fn set_depth(&mut self, depth: Option<usize>) {
+        self.depth = depth;
+    }
+    /// returns the most plies searched from the position
+    pub fn get_depth(&self) -> Option<usize> {
+        self.depth
+    }
+    /// Lowers the depth so that searching position goes through about nodes positions
+    /// at most before pruning, small game trees are searched as deep as the depth set before
+    pub fn limit_nodes(&mut self, position: &Position, nodes: u64) {
+        let free = position.get_free_cells().count();
+        let mut count: u64 = 1;
+        let mut depth = 0;
+        while depth < free {
+            count = count.saturating_mul((free - depth) as u64);
+            if count > nodes {
+                break;
+            }
+            depth += 1;
+        }
+        if depth < free {
+            let depth = depth.max(1);
+            self.depth = Some(self.depth.map_or(depth, |limit| limit.min(depth)));
+        }
+    }
+    /// Returns the index of the best move for the player whose turn it is and its score
+    /// or None if there is no free cell
+    pub fn best_move(&mut self, position: &Position) -> Option<(usize, i32)> {
+        self.prepare(position);
+        let mut position = position.clone();
+        let player = position.get_player_turn();
+        let free = position.get_free_cells();
+        let depth = self.root_depth(&position);
+        if let Some(index) = free
+            .indexes()
+            .find(|&index| depth > 0 && position.is_winning_index(index, player))
+        {
+            return Some((index, WIN_SCORE - 1));
+        }
+        let mut best = None;
+        let mut alpha = -INFINITY;
+        for index in self.ordered(free) {
+            position.play(index);
+            let score = if position.is_full() || depth == 0 {
+                0
+            } else {
+                let (beta, alpha) = (to_child(INFINITY), to_child(alpha));
+                to_parent(self.negamax(&mut position, beta, alpha, depth - 1))
+            };
+            position.unplay(index);
+            if best.is_none() || score > alpha {
+                alpha = score;
+                best = Some((index, score));
+            }
+        }
+        best
+    }
+    /// Returns every move of the player whose turn it is, in the order of the
+    /// cells, with the outcome it leads to and the plies until the game is won or lost
+    ///
+    /// The outcome is None when the depth limit hides how the game ends.
+    pub fn analyze(&mut self, position: &Position) -> Vec<(Move, Option<Outcome>, usize)> {
+        let player = position.get_player_turn();
+        let limited = self.root_depth(position) < position.get_free_cells().count();
+        self.scores(position)
+            .into_iter()
+            .map(|(index, score)| {
+                let (row, column) = position.location(index);
+                let (outcome, plies) = match Outcome::from_score(score) {
+                    (Outcome::Draw, _) if limited => (None, 0),
+                    (outcome, plies) => (Some(outcome), plies),
+                };
+                (Move::new(player, row, column), outcome, plies)
+            })
+            .collect()
+    }
+    /// Returns the index and the score of every move of the player whose turn it is
+    /// in the order of the cells, unlike best_move no score is only a bound
+    pub fn scores(&mut self, position: &Position) -> Vec<(usize, i32)> {
+        self.prepare(position);
+        let mut position = position.clone();
+        let player = position.get_player_turn();
+        let depth = self.root_depth(&position);
+        let mut scores = Vec::new();
+        for index in position.get_free_cells().indexes() {
+            let score = if depth == 0 {
+                0
+            } else if position.is_winning_index(index, player) {
+                WIN_SCORE - 1
+            } else {
+                position.play(index);
+                let score = if position.is_full() {
+                    0
+                } else {
+                    to_parent(self.negamax(&mut position, -INFINITY, INFINITY, depth - 1))
+                };
+                position.unplay(index);
+                score
+            };
+            scores.push((index, score));
+        }
+        scores
+    }
+    /// Returns the index of every move of the player whose turn it is in the order
+    /// of the cells and whether it is one of the best ones
+    ///
+    /// Only the best score is searched exactly, the other moves are only checked
+    /// to be worse, so it is much faster than scores on big playgrounds.
+    pub fn best_moves(&mut self, position: &Position) -> Vec<(usize, bool)> {
+        let best = match self.best_move(position) {
+            Some((_, score)) => score,
+            None => return Vec::new(),
+        };
+        let mut position = position.clone();
+        let player = position.get_player_turn();
+        let depth = self.root_depth(&position);
+        let mut moves = Vec::new();
+        for index in position.get_free_cells().indexes() {
+            let is_best = if depth == 0 {
+                true
+            } else if position.is_winning_index(index, player) {
+                best == WIN_SCORE - 1
+            } else {
+                position.play(index);
+                let score = if position.is_full() {
+                    0
+                } else {
+                    // a null window around the best score only tells if the move reaches it
+                    let (alpha, beta) = (to_child(best), to_child(best - 1));
+                    to_parent(self.negamax(&mut position, alpha, beta, depth - 1))
+                };
+                position.unplay(index);
+                score >= best
+            };
+            moves.push((index, is_best));
+        }
+        moves
+    }
+    /// Returns the plies searched from position, the whole game tree is not deeper
+    /// than its free cells
+    fn root_depth(&self, position: &Position) -> usize {
+        let free = position.get_free_cells().count();
+        self.depth.map_or(free, |depth| depth.min(free))
+    }
+    /// Clears the table if it was filled for another playground
+    fn prepare(&mut self, position: &Position) {
+        let (width, height) = (position.get_width(), position.get_height());
+        let size = (width, height, position.get_win_length());
+        if self.size == size {
+            return;
+        }
+        self.size = size;
+        self.table.clear();
+        self.order = (0..width * height).collect();
+        self.order.sort_by_key(|&index| {
+            // distances are doubled to stay in integers
+            let (row, column) = position.location(index);
+            let d_row = (2 * row as isize - width as isize - 1).abs();
+            let d_column = (2 * column as isize - height as isize - 1).abs();
+            d_row * d_row + d_column * d_column
+        });
+    }
+    /// Returns the free cells ordered by their distance from the center
+    /// to let alpha-beta pruning cut more branches.
+    fn ordered(&self, free: Bitboard) -> Vec<usize> {
+        self.order
+            .iter()
+            .copied()
+            .filter(|&index| free.get(index))
+            .collect()
+    }
+    /// Returns the score of the position for the player whose turn it is
+    /// searching depth plies below it
+    fn negamax(
+        &mut self,
+        position: &mut Position,
+        mut alpha: i32,
+        mut beta: i32,
+        depth: usize,
+    ) -> i32 {
+        if depth == 0 {
+            return 0;
+        }
+        let player = position.get_player_turn();
+        let opponent = player.opponent();
+        let free = position.get_free_cells();
+        if free
+            .indexes()
+            .any(|index| position.is_winning_index(index, player))
+        {
+            return WIN_SCORE - 1;
+        }
+        let threats: Vec<usize> = free
+            .indexes()
+            .filter(|&index| position.is_winning_index(index, opponent))
+            .collect();
+        if threats.len() > 1 {
+            // only one of them can be blocked
+            return 2 - WIN_SCORE;
+        }
+
+        let key = (
+            position.get_cells(Cell::X),
+            position.get_cells(Cell::O),
+            player,
+        );
+        if let Some(entry) = self.table.get(&key).filter(|entry| entry.depth >= depth) {
+            match entry.bound {
+                Bound::Exact => return entry.score,
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return entry.score;
+            }
+        }
+        let searched_alpha = alpha;
+
+        let moves = if threats.len() == 1 {
+            threats
+        } else {
+            self.ordered(free)
+        };
+        let mut best = -INFINITY;
+        for index in moves {
+            position.play(index);
+            let score = if position.is_full() {
+                0
+            } else {
+                to_parent(self.negamax(position, to_child(beta), to_child(alpha), depth - 1))
+            };
+            position.unplay(index);
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= searched_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.table.insert(
+            key,
+            Entry {
+                score: best,
+                bound,
+                depth,
+            },
+        );
+        best
+    }
+}
+
+/// Returns every move of the player whose turn it is with the outcome it leads
+/// to and the plies until the game is won or lost, see Search::analyze
+///
+/// The search goes through ANALYZE_NODES positions at most before pruning,
+/// so big playgrounds get the outcomes found within a few moves.
+pub fn analyze(position: &Position) -> Vec<(Move, Option<Outcome>, usize)> {
+    let mut search = Search::new();
+    search.limit_nodes(position, ANALYZE_NODES);
+    search.analyze(position)
+}
+/// Converts the score of a position to the score of the move that reached it
+fn to_parent(score: i32) -> i32 {
+    -score + score.signum()
+}
+/// Converts a bound on the score of a move to a bound on the score of the position it reaches
+fn to_child(score: i32) -> i32 {
+    -(score + score.signum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_core::Controller;
+
+    /// Returns a 3x3 position after X and O play the indexes in turn
+    fn position(indexes: &[usize]) -> Position {
+        let mut position = Position::new(3, 3, 3);
+        for &index in indexes.iter() {
+            position.play(index);
+        }
+        position
+    }
+
+    #[test]
+    fn empty_playground_is_draw() {
+        let best = Search::new().best_move(&position(&[]));
+        assert_eq!(best.map(|(_, score)| score), Some(0));
+    }
+
+    #[test]
+    fn takes_immediate_win() {
+        // X: 0, 1  O: 3, 4
+        let best = Search::new().best_move(&position(&[0, 3, 1, 4]));
+        assert_eq!(best, Some((2, WIN_SCORE - 1)));
+    }
+
+    #[test]
+    fn blocks_opponent_line() {
+        // X: 0, 8  O: 1, 4
+        let best = Search::new().best_move(&position(&[0, 1, 8, 4]));
+        assert_eq!(best.map(|(index, _)| index), Some(7));
+    }
+
+    #[test]
+    fn full_playground_has_no_move() {
+        let best = Search::new().best_move(&position(&[0, 1, 2, 4, 3, 5, 7, 6, 8]));
+        assert_eq!(best, None);
+    }
+
+    #[test]
+    fn limits_nodes_on_big_playgrounds() {
+        let mut search = Search::new();
+        search.limit_nodes(&position(&[]), 1_000_000);
+        assert_eq!(search.get_depth(), None);
+        // 225 * 224 positions fit but not 225 * 224 * 223
+        search.limit_nodes(&Position::new(15, 15, 5), 1_000_000);
+        assert_eq!(search.get_depth(), Some(2));
+        search.limit_nodes(&Position::new(15, 15, 5), 1);
+        assert_eq!(search.get_depth(), Some(1));
+    }
+
+    #[test]
+    fn same_cells_with_other_player_to_move() {
+        // X: 0, 3  O: 1, 5 with X to move and with O to move
+        let x_turn = position(&[0, 1, 3, 5]);
+        let mut controller = Controller::from(&x_turn);
+        controller.set_player_turn(Cell::O);
+        let o_turn = Position::from(&controller);
+        let mut search = Search::new();
+        search.scores(&x_turn);
+        assert_eq!(search.scores(&o_turn), Search::new().scores(&o_turn));
+    }
+
+    #[test]
+    fn scores_map_to_outcomes_and_plies() {
+        assert_eq!(Outcome::from_score(WIN_SCORE - 1), (Outcome::Win, 1));
+        assert_eq!(Outcome::from_score(WIN_SCORE - 5), (Outcome::Win, 5));
+        assert_eq!(Outcome::from_score(0), (Outcome::Draw, 0));
+        assert_eq!(Outcome::from_score(2 - WIN_SCORE), (Outcome::Loss, 2));
+        assert_eq!(Outcome::from_score(6 - WIN_SCORE), (Outcome::Loss, 6));
+    }
+
+    #[test]
+    fn analyzes_every_free_cell() {
+        // X: 0, 1  O: 3, 4
+        let position = position(&[0, 3, 1, 4]);
+        let analysis = analyze(&position);
+        let indexes: Vec<usize> = analysis
+            .iter()
+            .map(|(m, _, _)| position.index(m.row, m.column))
+            .collect();
+        assert_eq!(indexes, vec![2, 5, 6, 7, 8]);
+        assert!(analysis.iter().all(|(m, _, _)| m.player == Cell::X));
+        assert_eq!((analysis[0].1, analysis[0].2), (Some(Outcome::Win), 1));
+        // anything but winning or blocking at 5 lets O finish its line
+        for (_, outcome, plies) in analysis[2..].iter() {
+            assert_eq!((*outcome, *plies), (Some(Outcome::Loss), 2));
+        }
+    }
+
+    #[test]
+    fn analysis_matches_best_move() {
+        // X: 0  O: 4
+        let position = position(&[0, 4]);
+        let best = Search::new().best_move(&position).map(|(_, score)| score);
+        let scores = Search::new().scores(&position);
+        assert_eq!(scores.len(), 7);
+        assert_eq!(scores.iter().map(|&(_, score)| score).max(), best);
+        assert!(analyze(&position)
+            .iter()
+            .all(|(_, outcome, _)| *outcome == Some(Outcome::Draw)));
+    }
+
+    #[test]
+    fn full_playground_has_no_analysis() {
+        assert!(analyze(&position(&[0, 1, 2, 4, 3, 5, 7, 6, 8])).is_empty());
+    }
+
+    #[test]
+    fn best_moves_are_the_best_scored_ones() {
+        for indexes in [&[][..], &[0], &[4], &[0, 4], &[0, 3, 1, 4], &[0, 1, 8, 4]].iter() {
+            let position = position(indexes);
+            let scores = Search::new().scores(&position);
+            let best = scores.iter().map(|&(_, score)| score).max();
+            let expected: Vec<(usize, bool)> = scores
+                .iter()
+                .map(|&(index, score)| (index, Some(score) == best))
+                .collect();
+            assert_eq!(
+                Search::new().best_moves(&position),
+                expected,
+                "{:?}",
+                indexes
+            );
+        }
+    }
+
+    #[test]
+    fn limit_nodes_keeps_a_smaller_depth() {
+        let mut search = Search::new();
+        search.set_depth(Some(1));
+        search.limit_nodes(&Position::new(15, 15, 5), 1_000_000);
+        assert_eq!(search.get_depth(), Some(1));
+        search.set_depth(Some(4));
+        search.limit_nodes(&Position::new(15, 15, 5), 1_000_000);
+        assert_eq!(search.get_depth(), Some(2));
+        search.limit_nodes(&position(&[]), 1_000_000);
+        assert_eq!(search.get_depth(), Some(2));
+    }
+
+    #[test]
+    fn limited_depth_sees_only_close_ends() {
+        // X: 0, 1  O: 3, 4
+        let mut search = Search::new();
+        search.set_depth(Some(1));
+        let scores = search.scores(&position(&[0, 3, 1, 4]));
+        assert_eq!(scores[0], (2, WIN_SCORE - 1));
+        assert!(scores[1..].iter().all(|&(_, score)| score == 0));
+        search.set_depth(Some(0));
+        let scores = search.scores(&position(&[0, 3, 1, 4]));
+        assert!(scores.iter().all(|&(_, score)| score == 0));
+        assert_eq!(scores.len(), 5);
+    }
+
+    #[test]
+    fn analysis_of_big_playgrounds_is_limited() {
+        // X: b1, b2  O: a1 on 5x5 where 4 in a line win
+        let mut position = Position::new(5, 5, 4);
+        for &(row, column) in [(2, 1), (1, 1), (2, 2)].iter() {
+            position.play(position.index(row, column));
+        }
+        let analysis = analyze(&position);
+        assert_eq!(analysis.len(), 22);
+        assert!(analysis.iter().any(|(_, outcome, _)| outcome.is_none()));
+        assert!(analysis
+            .iter()
+            .all(|(_, outcome, _)| *outcome != Some(Outcome::Draw)));
+    }
+}
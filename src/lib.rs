@@ -6,262 +6,44 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at http://mozilla.org/MPL/2.0/.
 */
 
+//! Classic Tic-Tac-Toe game that can also be played on bigger playgrounds.
+//!
+//! The game rules live in [`Controller`], which keeps the playground, the
+//! player turn and the move history. [`Position`] is a compact copy of the
+//! playground used by the computer players: [`ComputerPlayer`] plays in
+//! several [`Level`]s and [`Search`] finds the best move by searching the
+//! whole game tree. [`MctsPlayer`] plays bigger playgrounds with Monte Carlo
+//! tree search. Humans, computers and scripts are all a [`Player`] and
+//! [`play_game`] lets any two of them play. [`notation`] reads and writes
+//! locations like `b2`. [`run`] starts the terminal game built on top of them.
+//!
+//! ```
+//! use tic_tac_toe::{Cell, ComputerPlayer, Controller, GameStatus, Level, Move, Player, Position};
+//!
+//! let mut controller = Controller::new();
+//! controller.try_play(Move::new(Cell::X, 2, 2)).unwrap();
+//!
+//! let mut computer_player = ComputerPlayer::new();
+//! computer_player.set_level(Level::Perfect);
+//! let answer = computer_player.choose_move(&Position::from(&controller));
+//! assert_eq!(controller.try_play(answer), Ok(GameStatus::InProgress));
+//! ```
+
+mod config;
 mod game_core;
-use crate::game_core::{Cell, ComputerPlayer, Controller, Level};
-
-use std::io::{self, Write};
-use std::process;
-
-/// Starts the game and communicate with the player
-pub fn run() {
-    let mut controller = Controller::new();
-    let mut computer_player = ComputerPlayer::new();
-    show_intro();
-    loop {
-        input_mode(&mut controller, &mut computer_player);
-        loop {
-            if computer_player.get_active() {
-                computer_player.play_turn(&mut controller);
-                let winner = controller.get_winner();
-                if winner != Cell::Empty {
-                    show_playground(&controller);
-                    println!("Winner is {}!\nGame Over!", winner.to_str());
-                    break;
-                }
-
-                if !controller.can_continue() {
-                    show_playground(&controller);
-                    println!("No way to continue!\nGame Over!");
-                    break;
-                }
-            }
-
-            input_play(&mut controller);
-
-            let winner = controller.get_winner();
-            if winner != Cell::Empty {
-                show_playground(&controller);
-                println!("Winner is {}!\nGame Over!", winner.to_str());
-                break;
-            }
-
-            if !controller.can_continue() {
-                show_playground(&controller);
-                println!("No way to continue!\nGame Over!");
-                break;
-            }
-        }
-    }
-}
-/// Inputs mode that player wants.
-fn input_mode(c: &mut Controller, cp: &mut ComputerPlayer) {
-    println!("\nType in Mode field: ");
-    println!("    1 => play with computer.");
-    println!("    2 => play with another person.");
-    println!("    3 => last used option(or Default).");
-    println!("    0 => exit the game.");
-    let mut mode = String::new();
-    c.initialize(); // initialize the game
-
-    print!("Mode: ");
-    io::stdout().flush().expect("flush failed!");
-
-    io::stdin()
-        .read_line(&mut mode)
-        .expect("Failed to read line");
-
-    let mode: i32 = match mode.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Input only specified numbers!");
-            input_mode(c, cp);
-            return;
-        }
-    };
-
-    match mode {
-        0 => process::exit(0),
-        1 => {
-            cp.set_active(true);
-            input_cp_info(c, cp);
-        }
-        2 => {
-            cp.set_active(false);
-        }
-        3 => {}
-        _ => {
-            println!("Input only specified numbers!");
-            input_mode(c, cp);
-        }
-    }
-}
-fn input_cp_info(c: &mut Controller, cp: &mut ComputerPlayer) {
-    println!("\nType in Level field: ");
-    println!("    0 => back to last step.");
-    println!("    1 => play in stupid level.");
-    println!("    2 => play in normal level.");
-    println!("    3 => play in smart level.");
-    loop {
-        let mut level = String::new();
-        print!("Level: ");
-        io::stdout().flush().expect("flush failed!");
-
-        io::stdin()
-            .read_line(&mut level)
-            .expect("Failed to read line");
-
-        let level: i32 = match level.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Input only specified numbers!");
-                continue;
-            }
-        };
-
-        match level {
-            0 => {
-                input_mode(c, cp);
-                return;
-            }
-            1 => cp.set_level(Level::Stupid),
-            2 => cp.set_level(Level::Normal),
-            3 => cp.set_level(Level::Smart),
-            _ => {
-                println!("Input only specified numbers!");
-                continue;
-            }
-        }
-        break;
-    }
-    println!("\nType in Player field: ");
-    println!("    0 => back to last step.");
-    println!("    1 => you will be Player X.");
-    println!("    2 => you will be Player O.");
-    loop {
-        let mut player_opponent = String::new();
-        print!("Player: ");
-        io::stdout().flush().expect("flush failed!");
-
-        io::stdin()
-            .read_line(&mut player_opponent)
-            .expect("Failed to read line");
-
-        let player_opponent: i32 = match player_opponent.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Input only specified numbers!");
-                continue;
-            }
-        };
-
-        match player_opponent {
-            0 => {
-                input_cp_info(c, cp);
-                return;
-            }
-            1 => cp.set_player_turn(Cell::O),
-            2 => cp.set_player_turn(Cell::X),
-            _ => {
-                println!("Input only specified numbers!");
-                continue;
-            }
-        }
-        break;
-    }
-}
-/// Inputs player choice and plays it.
-fn input_play(c: &mut Controller) {
-    let mut choice = String::new();
-
-    show_playground(c);
-    print!("Player {}: ", c.get_player_turn().to_str());
-    io::stdout().flush().expect("flush failed!");
-
-    io::stdin()
-        .read_line(&mut choice)
-        .expect("Failed to read line");
-
-    let choice: i32 = match choice.trim().parse() {
-        Ok(num) => num,
-        Err(_) => {
-            println!("Input only specified numbers!");
-            return;
-        }
-    };
-
-    let (row, column);
-    match choice {
-        0 => process::exit(0),
-        1 => {
-            row = 1;
-            column = 1;
-        }
-        2 => {
-            row = 2;
-            column = 1;
-        }
-        3 => {
-            row = 3;
-            column = 1;
-        }
-        4 => {
-            row = 1;
-            column = 2;
-        }
-        5 => {
-            row = 2;
-            column = 2;
-        }
-        6 => {
-            row = 3;
-            column = 2;
-        }
-        7 => {
-            row = 1;
-            column = 3;
-        }
-        8 => {
-            row = 2;
-            column = 3;
-        }
-        9 => {
-            row = 3;
-            column = 3;
-        }
-        _ => {
-            println!("Input only specified numbers!");
-            return;
-        }
-    }
-
-    if !c.is_free(row, column) {
-        println!("Choose empty location!");
-    } else {
-        c.play_turn(row, column);
-    }
-}
-/// Prints the playground.
-pub fn show_playground(c: &Controller) {
-    println!("-------------");
-    for column in 1..4 {
-        for row in 1..4 {
-            if row == 1 {
-                print!("| ");
-            }
-            if c.get_content(row, column) != Cell::Empty {
-                print!("{} | ", c.get_content(row, column).to_str());
-            } else {
-                print!("{} | ", (column - 1) * 3 + row);
-            }
-        }
-        println!();
-        println!("-------------");
-    }
-}
-/// Prints how to play the game.
-pub fn show_intro() {
-    println!("Copyright (C) 2020 Ahmad Amr Siam.\n");
-    println!("Hello in Tic Tac Toe game!");
-    println!("When the game starts type the number of the cell");
-    println!("you want to fill or 0 to exit the game.");
-}
+mod locale;
+mod names;
+mod options;
+mod scoreboard;
+mod terminal;
+mod theme;
+mod tui;
+
+pub use crate::game_core::notation;
+pub use crate::game_core::rand::Rng;
+pub use crate::game_core::{
+    analyze, play_game, Action, Bitboard, Budget, Cell, ComputerPlayer, Controller, Difficulty,
+    GameStatus, Level, MctsPlayer, Move, MoveError, Outcome, Player, Position, ScriptedPlayer,
+    Search, ANALYZE_NODES, MAX_CELLS, WIN_SCORE,
+};
+pub use crate::terminal::{run, show_intro, show_playground};
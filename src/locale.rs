@@ -0,0 +1,669 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::notation::ReadError;
+use crate::game_core::MoveError;
+use std::env;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Messages shown to the players in one language
+pub struct Language {
+    /// ISO 639-1 code used in LANG, the config and the command line
+    pub code: &'static str,
+    /// whether the language is written from right to left
+    right_to_left: bool,
+    /// (key, message) pairs, `{}` in a message is replaced by the arguments in order
+    messages: &'static [(&'static str, &'static str)],
+}
+
+/// All languages, the first one is used for the messages missing in the others
+pub const LANGUAGES: [Language; 2] = [
+    Language {
+        code: "en",
+        right_to_left: false,
+        messages: ENGLISH,
+    },
+    Language {
+        code: "ar",
+        right_to_left: true,
+        messages: ARABIC,
+    },
+];
+
+/// Index of the chosen language in LANGUAGES
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the index of the language with this code, the country and
+/// encoding of locale names like `ar_EG.UTF-8` are ignored
+pub fn find(code: &str) -> Option<usize> {
+    let code = code
+        .split(['_', '.', '-', '@'])
+        .next()
+        .unwrap_or("")
+        .to_lowercase();
+    LANGUAGES.iter().position(|language| language.code == code)
+}
+/// Returns the index of the language of the environment or None if it is not known
+pub fn from_env() -> Option<usize> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| find(&value))
+}
+/// Chooses the language used from now on
+pub fn set(index: usize) {
+    if index < LANGUAGES.len() {
+        CURRENT.store(index, Ordering::Relaxed);
+    }
+}
+/// Returns the language used now
+pub fn current() -> &'static Language {
+    &LANGUAGES[CURRENT.load(Ordering::Relaxed)]
+}
+/// Returns the message of key in the current language
+pub fn text(key: &str) -> &'static str {
+    let find = |language: &Language| {
+        language
+            .messages
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, message)| *message)
+    };
+    let message = find(current()).or_else(|| find(&LANGUAGES[0]));
+    debug_assert!(message.is_some(), "no message has the key {}", key);
+    message.unwrap_or("")
+}
+/// Returns the message of key with each `{}` replaced by the next argument
+///
+/// Arguments of right to left messages are isolated so names and numbers
+/// written from left to right keep their order.
+pub fn fill(key: &str, args: &[&dyn Display]) -> String {
+    let mut parts = text(key).split("{}");
+    let mut message = parts.next().unwrap_or("").to_string();
+    for (i, part) in parts.enumerate() {
+        let arg = args.get(i).map(|arg| arg.to_string()).unwrap_or_default();
+        if current().right_to_left {
+            message.push_str(&format!("\u{2068}{}\u{2069}", arg));
+        } else {
+            message.push_str(&arg);
+        }
+        message.push_str(part);
+    }
+    message
+}
+/// Returns line isolated from left to right, so a playground row keeps the order
+/// of its cells in right to left messages or with right to left marks, other
+/// lines are left as they are to keep the plain output plain
+pub fn left_to_right(line: &str) -> String {
+    if current().right_to_left || line.chars().any(is_right_to_left) {
+        format!("\u{2066}{}\u{2069}", line)
+    } else {
+        line.to_string()
+    }
+}
+/// Checks if c is a letter of a script written from right to left
+fn is_right_to_left(c: char) -> bool {
+    matches!(c as u32, 0x0590..=0x08ff | 0xfb1d..=0xfdff | 0xfe70..=0xfeff | 0x10800..=0x10fff | 0x1e800..=0x1efff)
+}
+/// Returns the message of the reason a saved game is refused in the current language
+pub fn read_error_text(error: &ReadError) -> String {
+    match error {
+        ReadError::Size => text("load.size").to_string(),
+        ReadError::WinLength => text("load.win_length").to_string(),
+        ReadError::SizeWinLength => text("load.size_win_length").to_string(),
+        ReadError::TooBig => text("load.too_big").to_string(),
+        ReadError::FirstPlayer => text("load.first").to_string(),
+        ReadError::Moves => text("load.moves").to_string(),
+        ReadError::Location(location) => fill("load.location", &[location]),
+        ReadError::Move(location, error) => fill("load.move", &[location, &error_text(*error)]),
+    }
+}
+/// Returns the message of the error in the current language
+pub fn error_text(error: MoveError) -> &'static str {
+    text(match error {
+        MoveError::OutOfBounds => "error.out_of_bounds",
+        MoveError::Occupied => "error.occupied",
+        MoveError::GameOver => "error.game_over",
+        MoveError::WrongPlayer => "error.wrong_player",
+    })
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("intro.copyright", "Copyright (C) 2020 Ahmad Amr Siam."),
+    ("intro.hello", "Hello in Tic Tac Toe game!"),
+    (
+        "intro.cells",
+        "When the game starts type the number of the cell\n\
+         you want to fill, its location like b3 (letter b on top,\n\
+         line 3 on the left) or its line,cell pair like 3,2 for b3.",
+    ),
+    (
+        "intro.help",
+        "Type help during the game to see the other commands.",
+    ),
+    ("field.type", "Type in {} field: "),
+    ("field.mode", "Mode"),
+    ("field.level", "Level"),
+    ("field.player", "Player"),
+    ("field.first", "First"),
+    ("field.match", "Match"),
+    ("field.x_level", "Player X level"),
+    ("field.o_level", "Player O level"),
+    ("field.games", "Games"),
+    ("field.target", "Target"),
+    ("field.delay", "Delay"),
+    ("field.name", "Name"),
+    ("field.mark", "Mark"),
+    ("input.numbers", "Input only specified numbers!"),
+    ("mode.computer", "play with computer."),
+    ("mode.person", "play with another person."),
+    ("mode.last", "last used option({})."),
+    ("mode.watch", "watch computer play with computer."),
+    ("mode.names", "change the names and marks of the players."),
+    ("mode.exit", "exit the game."),
+    ("menu.back", "back to last step."),
+    ("level.option", "play in {} level."),
+    ("level.stupid", "stupid"),
+    ("level.normal", "normal"),
+    ("level.smart", "smart"),
+    ("level.unbeatable", "unbeatable"),
+    ("level.number", "{}/{}"),
+    ("level.custom", "custom"),
+    ("player.x", "you will be Player X."),
+    ("player.o", "you will be Player O."),
+    ("first.x", "Player X starts."),
+    ("first.o", "Player O starts."),
+    ("first.alternate", "players take turns to start."),
+    ("first.random", "random player starts."),
+    (
+        "match.prompt",
+        "Type in Match field the number of games of the match,\n\
+         1 plays a single game.",
+    ),
+    (
+        "target.prompt",
+        "Type in Target field the percent of games you want to win, the computer\n\
+         makes more or fewer mistakes to follow your results, or 0 to keep its level.",
+    ),
+    (
+        "games.prompt",
+        "Type in Games field the number of games to play,\n\
+         1 to watch the game or more to see only the results.",
+    ),
+    (
+        "delay.prompt",
+        "Type in Delay field the milliseconds to wait before each move.",
+    ),
+    (
+        "name.prompt",
+        "Type in Name field the name of {}, or nothing to keep it.",
+    ),
+    (
+        "mark.prompt",
+        "Type in Mark field one character or emoji for {}, or nothing to keep {}.",
+    ),
+    ("name.player", "Player {}"),
+    ("name.you", "You"),
+    ("name.computer", "Computer ({} level)"),
+    ("name.computer_x", "Computer X ({} level)"),
+    ("name.computer_o", "Computer O ({} level)"),
+    (
+        "name.control",
+        "The name must not contain control characters!",
+    ),
+    ("name.differ", "The names of the players must differ!"),
+    ("mark.one", "The mark must be one character or emoji!"),
+    ("mark.digit", "The mark must not be a digit!"),
+    ("mark.differ", "The marks of the players must differ!"),
+    (
+        "describe.computer",
+        "play with computer in {} level as Player {}",
+    ),
+    (
+        "describe.adaptive",
+        "play with computer from {} level as Player {} aiming at {}% of wins for you",
+    ),
+    ("describe.person", "play with another person"),
+    (
+        "describe.tally",
+        "{} games of {} level computer X with {} level computer O",
+    ),
+    (
+        "describe.watch",
+        "watch {} level computer X play with {} level computer O",
+    ),
+    ("describe.o_starts", "{}, Player O starts"),
+    ("describe.alternate", "{}, taking turns to start"),
+    ("describe.random", "{}, random first player"),
+    ("describe.best_of", "{} in best of {} games"),
+    ("result.resigned", "{} resigned!"),
+    ("result.wins", "{} wins!"),
+    ("result.draw", "No way to continue!"),
+    ("result.game_over", "Game Over!"),
+    ("match.game", "Game {} of best of {}!"),
+    ("match.draw", "The match is a draw {}-{}!"),
+    ("match.won", "{} won the match {}-{}!"),
+    ("score.board", "Scoreboard"),
+    ("score.summary", "Session summary"),
+    ("score.title", "{} ({} games):"),
+    ("score.line", "{} wins, {} draws, {} losses"),
+    ("score.empty", "no games yet"),
+    (
+        "tally.line",
+        "Player {} ({} level): {} wins, {} draws, {} losses.",
+    ),
+    ("config.failed", "Could not save the last used option: {}"),
+    (
+        "history.failed",
+        "Could not save the result in the history: {}",
+    ),
+    (
+        "tui.unavailable",
+        "Full screen is not available in this terminal!",
+    ),
+    ("tui.title", "Tic Tac Toe"),
+    ("tui.turn", "Turn:"),
+    ("tui.score", "Score:"),
+    ("tui.status", "Status:"),
+    ("tui.choose", "Choose a cell"),
+    ("tui.game_over", "Game Over! Press any key."),
+    (
+        "tui.keys",
+        "arrows/hjkl move  enter/space play  ? hint  u undo  r redo  q quit",
+    ),
+    ("play.nothing_undo", "Nothing to undo!"),
+    ("play.nothing_redo", "Nothing to redo!"),
+    ("play.no_hint", "There is no move to suggest!"),
+    ("hint.win_now", "Hint: {} wins now."),
+    ("hint.win", "Hint: {} wins in {} moves."),
+    ("hint.draw", "Hint: {} draws."),
+    ("hint.loss", "Hint: {} loses in {} moves at the latest."),
+    (
+        "hint.ahead",
+        "Hint: {} is the best move looking {} moves ahead.",
+    ),
+    ("play.saved", "The game is saved in {}."),
+    ("play.save_failed", "Could not save the game: {}"),
+    ("play.load_failed", "Could not load the game: {}"),
+    ("play.file_name", "Type the file name after {}!"),
+    (
+        "play.unknown",
+        "Input a location or a command, type help to see them!",
+    ),
+    (
+        "commands.intro",
+        "Type the number of a cell, its location like b3 or its line,cell\n\
+         pair like 3,2 for b3 to play in it, or one of these commands:",
+    ),
+    ("commands.help", "show this help."),
+    ("commands.undo", "take back your last move."),
+    ("commands.redo", "play the move you took back again."),
+    ("commands.hint", "show the best move and where it leads."),
+    ("commands.analyze", "show where every free cell leads."),
+    (
+        "analyze.legend",
+        "W: wins, D: draws, L: loses, the number counts the moves of both\n\
+         players until the end when both play their best.",
+    ),
+    (
+        "analyze.limited",
+        "W: wins, L: loses, the number counts the moves of both players until\n\
+         the end when both play their best, ?: no end within {} moves.",
+    ),
+    ("commands.save", "save the game in file."),
+    ("commands.load", "continue a game saved in file."),
+    ("commands.resign", "give up this game."),
+    ("commands.new", "start this game again."),
+    ("commands.menu", "stop this game and go back to the menu."),
+    ("option.unknown", "Unknown argument {}!"),
+    ("option.value", "{} needs a value!"),
+    ("option.number", "{} must be a number not less than {}!"),
+    ("option.percent", "{} must be a percent not more than 100!"),
+    ("option.mode", "Unknown mode {}!"),
+    ("option.level", "Unknown level {}!"),
+    ("option.player", "Unknown player {}!"),
+    ("option.first", "Unknown first player {}!"),
+    ("option.language", "Unknown language {}!"),
+    ("option.theme", "Unknown theme {}!"),
+    (
+        "usage",
+        "Usage: tic_tac_toe [OPTIONS]
+
+Starts the game directly when --mode is given, otherwise the menus
+start with the given options as the last used option.
+
+Options:
+    --mode pvp|pvc|cvc      play with another person, with computer
+                            or watch computer play with computer
+    --level LEVEL           computer level: stupid, normal, smart, unbeatable
+                            or a difficulty from 1 to 10 between them
+    --play-as x|o           the player you are against the computer
+    --seed NUMBER           replay the same computer moves
+    --games NUMBER          number of games computers play each other
+    --first x|o|alternate|random
+                            who plays the first move of each game
+    --best-of NUMBER        number of games of a match with computer or
+                            another person
+    --adapt PERCENT         percent of the games you should win against the
+                            computer, it makes more or fewer mistakes to follow
+                            your results, 0 keeps the level fixed
+    --delay MILLISECONDS    time to wait before each move of watched computers
+    --name-x NAME, --name-o NAME
+                            names of the players shown in the messages
+    --mark-x MARK, --mark-o MARK
+                            one character or emoji shown in the playground
+                            instead of X or O
+    --lang en|ar            language of the messages, LANG is used when
+                            it is not given
+    --tui, --no-tui         play in the full screen terminal or line by line
+    --theme THEME           colors: plain, classic, ocean or contrast, the
+                            output is plain when NO_COLOR is set or it is
+                            not a terminal
+    --help                  print this help",
+    ),
+    ("load.size", "The size is missing or wrong!"),
+    ("load.win_length", "The win length is missing or wrong!"),
+    (
+        "load.size_win_length",
+        "The size does not fit the win length!",
+    ),
+    ("load.too_big", "The playground is too big!"),
+    ("load.first", "The first player is wrong!"),
+    ("load.moves", "The moves are missing!"),
+    ("load.location", "{} is not a location!"),
+    ("load.move", "{}: {}"),
+    (
+        "error.out_of_bounds",
+        "Choose location inside the playground!",
+    ),
+    ("error.occupied", "Choose empty location!"),
+    ("error.game_over", "The game is over!"),
+    ("error.wrong_player", "It is not your turn!"),
+];
+
+const ARABIC: &[(&str, &str)] = &[
+    ("intro.copyright", "حقوق النشر (C) 2020 أحمد عمرو صيام."),
+    ("intro.hello", "مرحبا بك في لعبة إكس أو!"),
+    (
+        "intro.cells",
+        "عندما تبدأ اللعبة اكتب رقم الخانة التي تريد ملأها،\n\
+         أو موقعها مثل b3 (الحرف b في الأعلى والسطر 3 على اليسار)\n\
+         أو رقمي السطر والخانة مثل 3,2 للخانة b3.",
+    ),
+    ("intro.help", "اكتب help أثناء اللعب لترى باقي الأوامر."),
+    ("field.type", "اكتب في خانة {}: "),
+    ("field.mode", "النمط"),
+    ("field.level", "المستوى"),
+    ("field.player", "اللاعب"),
+    ("field.first", "البادئ"),
+    ("field.match", "المباراة"),
+    ("field.x_level", "مستوى اللاعب X"),
+    ("field.o_level", "مستوى اللاعب O"),
+    ("field.games", "الأدوار"),
+    ("field.target", "الهدف"),
+    ("field.delay", "الانتظار"),
+    ("field.name", "الاسم"),
+    ("field.mark", "العلامة"),
+    ("input.numbers", "أدخل الأرقام المحددة فقط!"),
+    ("mode.computer", "العب مع الحاسوب."),
+    ("mode.person", "العب مع شخص آخر."),
+    ("mode.last", "آخر خيار مستخدم ({})."),
+    ("mode.watch", "شاهد الحاسوب يلعب مع الحاسوب."),
+    ("mode.names", "غيّر أسماء اللاعبين وعلاماتهم."),
+    ("mode.exit", "اخرج من اللعبة."),
+    ("menu.back", "ارجع إلى الخطوة السابقة."),
+    ("level.option", "العب في المستوى {}."),
+    ("level.stupid", "الغبي"),
+    ("level.normal", "العادي"),
+    ("level.smart", "الذكي"),
+    ("level.unbeatable", "الذي لا يُهزم"),
+    ("level.number", "{}/{}"),
+    ("level.custom", "المخصص"),
+    ("player.x", "ستكون اللاعب X."),
+    ("player.o", "ستكون اللاعب O."),
+    ("first.x", "يبدأ اللاعب X."),
+    ("first.o", "يبدأ اللاعب O."),
+    ("first.alternate", "يتناوب اللاعبان على البدء."),
+    ("first.random", "يبدأ لاعب عشوائي."),
+    (
+        "match.prompt",
+        "اكتب في خانة المباراة عدد أدوارها،\n\
+         و 1 يلعب دورا واحدا.",
+    ),
+    (
+        "target.prompt",
+        "اكتب في خانة الهدف النسبة المئوية للأدوار التي تريد الفوز بها، يكثر الحاسوب\n\
+         أو يقلل من أخطائه ليتبع نتائجك، أو 0 ليبقى على مستواه.",
+    ),
+    (
+        "games.prompt",
+        "اكتب في خانة الأدوار عدد الأدوار التي تُلعب،\n\
+         1 لمشاهدة الدور أو أكثر لرؤية النتائج فقط.",
+    ),
+    (
+        "delay.prompt",
+        "اكتب في خانة الانتظار عدد الأجزاء من الألف من الثانية قبل كل حركة.",
+    ),
+    (
+        "name.prompt",
+        "اكتب في خانة الاسم اسم {}، أو لا تكتب شيئا لإبقائه.",
+    ),
+    (
+        "mark.prompt",
+        "اكتب في خانة العلامة حرفا واحدا أو رمزا تعبيريا لـ {}، أو لا تكتب شيئا لإبقاء {}.",
+    ),
+    ("name.player", "اللاعب {}"),
+    ("name.you", "أنت"),
+    ("name.computer", "الحاسوب (المستوى {})"),
+    ("name.computer_x", "الحاسوب X (المستوى {})"),
+    ("name.computer_o", "الحاسوب O (المستوى {})"),
+    ("name.control", "يجب ألا يحتوي الاسم على محارف تحكم!"),
+    ("name.differ", "يجب أن يختلف اسما اللاعبين!"),
+    ("mark.one", "يجب أن تكون العلامة حرفا واحدا أو رمزا تعبيريا!"),
+    ("mark.digit", "يجب ألا تكون العلامة رقما!"),
+    ("mark.differ", "يجب أن تختلف علامتا اللاعبين!"),
+    (
+        "describe.computer",
+        "العب مع الحاسوب في المستوى {} بصفتك اللاعب {}",
+    ),
+    (
+        "describe.adaptive",
+        "العب مع الحاسوب بدءًا من المستوى {} بصفتك اللاعب {} بهدف فوزك في {}% من الأدوار",
+    ),
+    ("describe.person", "العب مع شخص آخر"),
+    (
+        "describe.tally",
+        "{} أدوار بين الحاسوب X في المستوى {} والحاسوب O في المستوى {}",
+    ),
+    (
+        "describe.watch",
+        "شاهد الحاسوب X في المستوى {} يلعب مع الحاسوب O في المستوى {}",
+    ),
+    ("describe.o_starts", "{}، يبدأ اللاعب O"),
+    ("describe.alternate", "{}، بالتناوب على البدء"),
+    ("describe.random", "{}، ببادئ عشوائي"),
+    ("describe.best_of", "{} في مباراة من {} أدوار"),
+    ("result.resigned", "استسلم {}!"),
+    ("result.wins", "فاز {}!"),
+    ("result.draw", "لا مجال للاستمرار!"),
+    ("result.game_over", "انتهت اللعبة!"),
+    ("match.game", "الدور {} من مباراة من {} أدوار!"),
+    ("match.draw", "انتهت المباراة بالتعادل {}-{}!"),
+    ("match.won", "فاز {} بالمباراة {}-{}!"),
+    ("score.board", "لوحة النتائج"),
+    ("score.summary", "ملخص الجلسة"),
+    ("score.title", "{} ({} أدوار):"),
+    ("score.line", "{} فوز، {} تعادل، {} خسارة"),
+    ("score.empty", "لم تُلعب أدوار بعد"),
+    (
+        "tally.line",
+        "اللاعب {} (المستوى {}): {} فوز، {} تعادل، {} خسارة.",
+    ),
+    ("config.failed", "تعذر حفظ آخر خيار مستخدم: {}"),
+    ("history.failed", "تعذر حفظ النتيجة في السجل: {}"),
+    ("tui.unavailable", "ملء الشاشة غير متاح في هذه الطرفية!"),
+    ("tui.title", "إكس أو"),
+    ("tui.turn", "الدور:"),
+    ("tui.score", "النتيجة:"),
+    ("tui.status", "الحالة:"),
+    ("tui.choose", "اختر خانة"),
+    ("tui.game_over", "انتهت اللعبة! اضغط أي مفتاح."),
+    (
+        "tui.keys",
+        "الأسهم/hjkl للتحريك  enter/space للعب  ? تلميح  u تراجع  r إعادة  q خروج",
+    ),
+    ("play.nothing_undo", "لا شيء للتراجع عنه!"),
+    ("play.nothing_redo", "لا شيء لإعادته!"),
+    ("play.no_hint", "لا توجد حركة لاقتراحها!"),
+    ("hint.win_now", "تلميح: {} تفوز الآن."),
+    ("hint.win", "تلميح: {} تفوز خلال {} حركات."),
+    ("hint.draw", "تلميح: {} تؤدي إلى التعادل."),
+    ("hint.loss", "تلميح: {} تخسر بعد {} حركات على الأكثر."),
+    (
+        "hint.ahead",
+        "تلميح: {} أفضل حركة عند النظر {} حركات إلى الأمام.",
+    ),
+    ("play.saved", "حُفظت اللعبة في {}."),
+    ("play.save_failed", "تعذر حفظ اللعبة: {}"),
+    ("play.load_failed", "تعذر تحميل اللعبة: {}"),
+    ("play.file_name", "اكتب اسم الملف بعد {}!"),
+    ("play.unknown", "أدخل موقعا أو أمرا، اكتب help لتراها!"),
+    (
+        "commands.intro",
+        "اكتب رقم خانة أو موقعها مثل b3 أو رقمي السطر والخانة\n\
+         مثل 3,2 للخانة b3 لتلعب فيها، أو أحد هذه الأوامر:",
+    ),
+    ("commands.help", "اعرض هذه المساعدة."),
+    ("commands.undo", "تراجع عن حركتك الأخيرة."),
+    ("commands.redo", "أعد لعب الحركة التي تراجعت عنها."),
+    ("commands.hint", "اعرض أفضل حركة وإلى أين تؤدي."),
+    ("commands.analyze", "اعرض إلى أين تؤدي كل خانة فارغة."),
+    (
+        "analyze.legend",
+        "W: فوز، D: تعادل، L: خسارة، والرقم عدد حركات اللاعبين\n\
+         حتى النهاية عندما يلعب كلاهما أفضل لعب.",
+    ),
+    (
+        "analyze.limited",
+        "W: فوز، L: خسارة، والرقم عدد حركات اللاعبين حتى النهاية عندما\n\
+         يلعب كلاهما أفضل لعب، ?: لا نهاية خلال {} حركات.",
+    ),
+    ("commands.save", "احفظ اللعبة في ملف."),
+    ("commands.load", "أكمل لعبة محفوظة في ملف."),
+    ("commands.resign", "استسلم في هذا الدور."),
+    ("commands.new", "ابدأ هذا الدور من جديد."),
+    ("commands.menu", "أوقف هذا الدور وارجع إلى القائمة."),
+    ("option.unknown", "وسيط غير معروف {}!"),
+    ("option.value", "يحتاج {} إلى قيمة!"),
+    ("option.number", "يجب أن يكون {} رقما لا يقل عن {}!"),
+    (
+        "option.percent",
+        "يجب أن يكون {} نسبة مئوية لا تزيد على 100!",
+    ),
+    ("option.mode", "نمط غير معروف {}!"),
+    ("option.level", "مستوى غير معروف {}!"),
+    ("option.player", "لاعب غير معروف {}!"),
+    ("option.first", "لاعب أول غير معروف {}!"),
+    ("option.language", "لغة غير معروفة {}!"),
+    ("option.theme", "ألوان غير معروفة {}!"),
+    (
+        "usage",
+        "الاستخدام: tic_tac_toe [OPTIONS]
+
+تبدأ اللعبة مباشرة عند إعطاء --mode، وإلا تبدأ القوائم
+والخيارات المعطاة هي آخر خيار مستخدم.
+
+الخيارات:
+    --mode pvp|pvc|cvc      العب مع شخص آخر أو مع الحاسوب
+                            أو شاهد الحاسوب يلعب مع الحاسوب
+    --level LEVEL           مستوى الحاسوب: stupid أو normal أو smart أو unbeatable
+                            أو صعوبة من 1 إلى 10 بينها
+    --play-as x|o           اللاعب الذي تلعبه ضد الحاسوب
+    --seed NUMBER           أعد حركات الحاسوب نفسها
+    --games NUMBER          عدد الألعاب التي يلعبها الحاسوبان معا
+    --first x|o|alternate|random
+                            من يلعب الحركة الأولى في كل لعبة
+    --best-of NUMBER        عدد ألعاب المباراة مع الحاسوب أو مع شخص آخر
+    --adapt PERCENT         نسبة الألعاب التي يجب أن تفوز بها ضد الحاسوب،
+                            فيخطئ أكثر أو أقل ليتبع نتائجك، و0 يثبت المستوى
+    --delay MILLISECONDS    مدة الانتظار قبل كل حركة من حركات الحاسوب المشاهد
+    --name-x NAME, --name-o NAME
+                            أسماء اللاعبين التي تظهر في الرسائل
+    --mark-x MARK, --mark-o MARK
+                            حرف أو رمز تعبيري واحد يظهر في ساحة اللعب
+                            بدلا من X أو O
+    --lang en|ar            لغة الرسائل، تستخدم LANG عند عدم إعطائها
+    --tui, --no-tui         العب في شاشة الطرفية الكاملة أو سطرا بسطر
+    --theme THEME           الألوان: plain أو classic أو ocean أو contrast،
+                            والمخرجات بلا ألوان عند ضبط NO_COLOR أو عندما
+                            لا تكون طرفية
+    --help                  اطبع هذه المساعدة",
+    ),
+    ("load.size", "الحجم مفقود أو خاطئ!"),
+    ("load.win_length", "طول الفوز مفقود أو خاطئ!"),
+    ("load.size_win_length", "الحجم لا يناسب طول الفوز!"),
+    ("load.too_big", "ساحة اللعب كبيرة جدا!"),
+    ("load.first", "اللاعب الأول خاطئ!"),
+    ("load.moves", "الحركات مفقودة!"),
+    ("load.location", "{} ليس موقعا!"),
+    ("load.move", "{}: {}"),
+    ("error.out_of_bounds", "اختر موقعا داخل ساحة اللعب!"),
+    ("error.occupied", "اختر موقعا فارغا!"),
+    ("error.game_over", "انتهت اللعبة!"),
+    ("error.wrong_player", "ليس دورك!"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn languages_have_the_same_keys() {
+        for language in LANGUAGES.iter() {
+            for (key, _) in language.messages.iter() {
+                for other in LANGUAGES.iter() {
+                    assert!(
+                        other.messages.iter().any(|(k, _)| k == key),
+                        "{} is missing in {}",
+                        key,
+                        other.code
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keys_are_unique() {
+        for language in LANGUAGES.iter() {
+            for (i, (key, _)) in language.messages.iter().enumerate() {
+                assert!(
+                    !language.messages[i + 1..].iter().any(|(k, _)| k == key),
+                    "{} is twice in {}",
+                    key,
+                    language.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "no message has the key no.such_key")]
+    fn unknown_key_is_caught() {
+        text("no.such_key");
+    }
+
+    #[test]
+    fn isolates_only_right_to_left_rows() {
+        assert_eq!(left_to_right("1 | X | O |"), "1 | X | O |");
+        assert_eq!(left_to_right("1 | ب | O |"), "\u{2066}1 | ب | O |\u{2069}");
+    }
+}
@@ -0,0 +1,357 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::Cell;
+use crate::locale;
+use std::sync::{Mutex, MutexGuard};
+
+/// Name and mark chosen by a player, None keeps the default one
+struct Identity {
+    name: Option<String>,
+    mark: Option<String>,
+}
+impl Identity {
+    const DEFAULT: Identity = Identity {
+        name: None,
+        mark: None,
+    };
+}
+
+/// Identities of player X and player O
+static IDENTITIES: Mutex<[Identity; 2]> = Mutex::new([Identity::DEFAULT, Identity::DEFAULT]);
+/// Names of who plays X and O in the current match, a computer is not named after
+/// the identity of the side it plays
+static PLAYING: Mutex<Option<[String; 2]>> = Mutex::new(None);
+
+/// Returns the name of the player shown in the messages: the name of who plays it
+/// in the current match, else the chosen name or "Player X" if none was chosen
+pub fn name(player: Cell) -> String {
+    let playing = PLAYING.lock().unwrap_or_else(|error| error.into_inner());
+    if let Some(names) = playing.as_ref() {
+        return names[slot(player)].clone();
+    }
+    let name = identities()[slot(player)].name.clone().unwrap_or_default();
+    chosen_or_default(player, &name)
+}
+/// Sets the names of who plays X and O in the current match, None goes back
+/// to the chosen names when the match is over
+pub fn set_playing(names: Option<[String; 2]>) {
+    *PLAYING.lock().unwrap_or_else(|error| error.into_inner()) = names;
+}
+/// Returns the name chosen for the player or None if it is the default one
+pub fn chosen_name(player: Cell) -> Option<String> {
+    identities()[slot(player)].name.clone()
+}
+/// Returns the mark of the player shown in the playground, X or O if none was chosen
+pub fn mark(player: Cell) -> String {
+    match identities()[slot(player)].mark.clone() {
+        Some(mark) => mark,
+        None => player.to_str().to_string(),
+    }
+}
+/// Sets the name of the player, an empty name goes back to the default one,
+/// the name must differ from the other player name whatever the letter case
+pub fn set_name(player: Cell, name: &str) -> Result<(), String> {
+    let name = name.trim();
+    if name.chars().any(char::is_control) {
+        return Err(locale::text("name.control").to_string());
+    }
+    let other = player.opponent();
+    let other_name = identities()[slot(other)].name.clone().unwrap_or_default();
+    if chosen_or_default(player, name).to_lowercase()
+        == chosen_or_default(other, &other_name).to_lowercase()
+    {
+        return Err(locale::text("name.differ").to_string());
+    }
+    identities()[slot(player)].name = if name.is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    };
+    Ok(())
+}
+/// Sets the mark of the player to a single character or emoji
+/// that is not a digit and differs from the other player mark
+pub fn set_mark(player: Cell, mark: &str) -> Result<(), String> {
+    let mark = mark.trim();
+    if !is_grapheme(mark) {
+        return Err(locale::text("mark.one").to_string());
+    }
+    if mark.chars().all(char::is_numeric) {
+        return Err(locale::text("mark.digit").to_string());
+    }
+    let other = player.opponent();
+    if self::mark(other) == mark {
+        return Err(locale::text("mark.differ").to_string());
+    }
+    identities()[slot(player)].mark = if mark == player.to_str() {
+        None
+    } else {
+        Some(mark.to_string())
+    };
+    Ok(())
+}
+/// Returns how many terminal columns the widest mark takes
+pub fn mark_width() -> usize {
+    display_width(&mark(Cell::X)).max(display_width(&mark(Cell::O)))
+}
+/// Returns text followed by spaces to fill width terminal columns
+pub fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(fill))
+}
+
+/// Returns how many terminal columns text takes, East Asian wide characters
+/// and emoji take two columns, combining characters and direction marks take none
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    // width of the last base character
+    let mut last = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_extending(c) {
+            if c == '\u{fe0f}' && last == 1 {
+                // emoji presentation of the character before it
+                width += 1;
+                last = 2;
+            }
+            continue;
+        }
+        if is_direction_mark(c) {
+            continue;
+        }
+        if c == '\u{200d}' {
+            // the joined emoji is drawn with the one before it
+            chars.next();
+            continue;
+        }
+        if is_regional_indicator(c) && chars.peek().copied().is_some_and(is_regional_indicator) {
+            chars.next();
+            width += 2;
+            last = 2;
+            continue;
+        }
+        last = if is_wide(c) { 2 } else { 1 };
+        width += last;
+    }
+    width
+}
+/// Checks if text is one user perceived character: a base character followed
+/// by combining characters, an emoji sequence joined by zero width joiners or a flag
+fn is_grapheme(text: &str) -> bool {
+    let mut chars = text.chars();
+    let first = match chars.next() {
+        Some(c) if !c.is_control() && !c.is_whitespace() && !is_extending(c) => c,
+        _ => return false,
+    };
+    let rest: Vec<char> = chars.collect();
+    if is_regional_indicator(first) {
+        return rest.len() == 1 && is_regional_indicator(rest[0]);
+    }
+    let mut after_joiner = false;
+    for &c in rest.iter() {
+        if after_joiner {
+            after_joiner = false;
+            if c.is_control() || c.is_whitespace() || is_extending(c) {
+                return false;
+            }
+        } else if c == '\u{200d}' {
+            after_joiner = true;
+        } else if !is_extending(c) {
+            return false;
+        }
+    }
+    !after_joiner
+}
+/// Checks if c belongs to the character before it: combining marks,
+/// variation selectors, skin tone modifiers and emoji tags
+fn is_extending(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036f
+        | 0x0483..=0x0489
+        | 0x0591..=0x05bd
+        | 0x0610..=0x061a
+        | 0x064b..=0x065f
+        | 0x0670
+        | 0x06d6..=0x06dc
+        | 0x06df..=0x06e4
+        | 0x0e31
+        | 0x0e34..=0x0e3a
+        | 0x0e47..=0x0e4e
+        | 0x1ab0..=0x1aff
+        | 0x1dc0..=0x1dff
+        | 0x20d0..=0x20ff
+        | 0xfe00..=0xfe0f
+        | 0xfe20..=0xfe2f
+        | 0x1f3fb..=0x1f3ff
+        | 0xe0020..=0xe007f
+        | 0xe0100..=0xe01ef)
+}
+/// Checks if c only changes the direction of the text around it
+fn is_direction_mark(c: char) -> bool {
+    matches!(c as u32, 0x200e..=0x200f | 0x202a..=0x202e | 0x2066..=0x2069)
+}
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1f1e6..=0x1f1ff)
+}
+/// Checks if c takes two terminal columns
+fn is_wide(c: char) -> bool {
+    matches!(c as u32,
+        0x1100..=0x115f
+        | 0x231a..=0x231b
+        | 0x23e9..=0x23ec
+        | 0x23f0
+        | 0x23f3
+        | 0x25fd..=0x25fe
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267f
+        | 0x2693
+        | 0x26a1
+        | 0x26aa..=0x26ab
+        | 0x26bd..=0x26be
+        | 0x26c4..=0x26c5
+        | 0x26ce
+        | 0x26d4
+        | 0x26ea
+        | 0x26f2..=0x26f3
+        | 0x26f5
+        | 0x26fa
+        | 0x26fd
+        | 0x2705
+        | 0x270a..=0x270b
+        | 0x2728
+        | 0x274c
+        | 0x274e
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27b0
+        | 0x27bf
+        | 0x2b1b..=0x2b1c
+        | 0x2b50
+        | 0x2b55
+        | 0x2e80..=0x303e
+        | 0x3041..=0x33ff
+        | 0x3400..=0x4dbf
+        | 0x4e00..=0x9fff
+        | 0xa000..=0xa4cf
+        | 0xac00..=0xd7a3
+        | 0xf900..=0xfaff
+        | 0xfe30..=0xfe4f
+        | 0xff00..=0xff60
+        | 0xffe0..=0xffe6
+        | 0x1f004
+        | 0x1f0cf
+        | 0x1f18e
+        | 0x1f191..=0x1f19a
+        | 0x1f200..=0x1f251
+        | 0x1f300..=0x1f64f
+        | 0x1f680..=0x1f6ff
+        | 0x1f7e0..=0x1f7eb
+        | 0x1f90c..=0x1f9ff
+        | 0x1fa70..=0x1faff
+        | 0x20000..=0x3fffd)
+}
+
+/// Returns the name or "Player X" if it is empty
+fn chosen_or_default(player: Cell, name: &str) -> String {
+    if name.is_empty() {
+        locale::fill("name.player", &[&player.to_str()])
+    } else {
+        name.to_string()
+    }
+}
+/// Returns the index of the player in IDENTITIES
+fn slot(player: Cell) -> usize {
+    match player {
+        Cell::O => 1,
+        _ => 0,
+    }
+}
+fn identities() -> MutexGuard<'static, [Identity; 2]> {
+    IDENTITIES.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn measures_wide_and_narrow_characters() {
+        assert_eq!(display_width("abc"), 3);
+        assert_eq!(display_width("井字棋"), 6);
+        assert_eq!(display_width("한글"), 4);
+        assert_eq!(display_width("٠١٢٣"), 4);
+        assert_eq!(display_width("أحمد"), 4);
+    }
+
+    #[test]
+    fn combining_marks_and_direction_marks_take_no_column() {
+        assert_eq!(display_width("e\u{301}"), 1);
+        assert_eq!(display_width("مُحَمَّد"), 4);
+        assert_eq!(display_width("\u{2066}ab\u{2069}\u{200f}"), 2);
+    }
+
+    #[test]
+    fn measures_emoji() {
+        assert_eq!(display_width("😀"), 2);
+        assert_eq!(display_width("👍🏽"), 2);
+        assert_eq!(display_width("👩\u{200d}💻"), 2);
+        assert_eq!(display_width("🇪🇬"), 2);
+        assert_eq!(display_width("❤\u{fe0f}"), 2);
+        assert_eq!(pad("😀", 3), "😀 ");
+    }
+
+    #[test]
+    fn finds_single_graphemes() {
+        for text in [
+            "X",
+            "井",
+            "e\u{301}",
+            "٣",
+            "👍🏽",
+            "👩\u{200d}💻",
+            "🇪🇬",
+            "❤\u{fe0f}",
+        ]
+        .iter()
+        {
+            assert!(is_grapheme(text), "{}", text);
+        }
+        for text in ["", "XO", " ", "\u{301}", "👩\u{200d}", "🇪", "🇪🇬🇪", "\t"].iter()
+        {
+            assert!(!is_grapheme(text), "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn refuses_digit_marks() {
+        assert!(set_mark(Cell::X, "7").is_err());
+        assert!(set_mark(Cell::X, "٣").is_err());
+        assert!(set_mark(Cell::X, "ab").is_err());
+    }
+
+    #[test]
+    fn playing_names_replace_chosen_ones() {
+        set_playing(Some(["Computer".to_string(), "Alice".to_string()]));
+        assert_eq!(name(Cell::X), "Computer");
+        assert_eq!(name(Cell::O), "Alice");
+        set_playing(None);
+        assert_eq!(name(Cell::X), locale::fill("name.player", &[&"X"]));
+    }
+
+    #[test]
+    fn refuses_the_name_of_the_other_player() {
+        assert!(set_name(Cell::O, &locale::fill("name.player", &[&"X"])).is_err());
+        assert!(set_name(Cell::O, "Alice").is_ok());
+        assert!(set_name(Cell::X, "alice ").is_err());
+        assert!(set_name(Cell::O, "").is_ok());
+        assert_eq!(chosen_name(Cell::O), None);
+    }
+}
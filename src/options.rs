@@ -0,0 +1,285 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Cell, Difficulty, Level};
+use crate::locale;
+use crate::terminal::{Mode, Start};
+use crate::theme;
+
+/// Options given in the command line arguments, None for the ones not given
+#[derive(Default)]
+pub struct Options {
+    pub mode: Option<Mode>,
+    pub level: Option<Difficulty>,
+    pub play_as: Option<Cell>,
+    pub seed: Option<u64>,
+    pub games: Option<u64>,
+    pub best_of: Option<u64>,
+    /// percent of the games the human should win, 0 keeps the level fixed
+    pub target_win_rate: Option<u64>,
+    pub start: Option<Start>,
+    pub delay: Option<u64>,
+    pub name_x: Option<String>,
+    pub name_o: Option<String>,
+    pub mark_x: Option<String>,
+    pub mark_o: Option<String>,
+    /// index of the language in locale::LANGUAGES
+    pub language: Option<usize>,
+    pub tui: Option<bool>,
+    /// index of the theme in theme::THEMES
+    pub theme: Option<usize>,
+    pub help: bool,
+}
+impl Options {
+    /// Parses the arguments without the program name and returns the options
+    /// or a message that describes the wrong argument
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--help" | "-h" => {
+                    options.help = true;
+                    continue;
+                }
+                "--tui" => {
+                    options.tui = Some(true);
+                    continue;
+                }
+                "--no-tui" => {
+                    options.tui = Some(false);
+                    continue;
+                }
+                _ => {}
+            }
+            let value = match args.next() {
+                Some(value) => value,
+                None if arg.starts_with("--") => return Err(locale::fill("option.value", &[&arg])),
+                None => return Err(locale::fill("option.unknown", &[&arg])),
+            };
+            match arg.as_str() {
+                "--mode" => options.mode = Some(parse_mode(&value)?),
+                "--level" => options.level = Some(parse_level(&value)?),
+                "--play-as" => options.play_as = Some(parse_player(&value)?),
+                "--seed" => options.seed = Some(parse_number(&arg, &value, 0)?),
+                "--games" => options.games = Some(parse_number(&arg, &value, 1)?),
+                "--first" => options.start = Some(parse_start(&value)?),
+                "--best-of" => options.best_of = Some(parse_number(&arg, &value, 1)?),
+                "--adapt" => match parse_number(&arg, &value, 0)? {
+                    target if target <= 100 => options.target_win_rate = Some(target),
+                    _ => return Err(locale::fill("option.percent", &[&arg])),
+                },
+                "--name-x" => options.name_x = Some(value),
+                "--name-o" => options.name_o = Some(value),
+                "--mark-x" => options.mark_x = Some(value),
+                "--mark-o" => options.mark_o = Some(value),
+                "--lang" => {
+                    options.language = Some(
+                        locale::find(&value).ok_or(locale::fill("option.language", &[&value]))?,
+                    )
+                }
+                "--delay" => options.delay = Some(parse_number(&arg, &value, 0)?),
+                "--theme" => {
+                    options.theme =
+                        Some(theme::find(&value).ok_or(locale::fill("option.theme", &[&value]))?)
+                }
+                _ => return Err(locale::fill("option.unknown", &[&arg])),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Parses the mode name as it is written in the command line
+pub fn parse_mode(value: &str) -> Result<Mode, String> {
+    match value {
+        "pvp" => Ok(Mode::Person),
+        "pvc" => Ok(Mode::Computer),
+        "cvc" => Ok(Mode::Computers),
+        _ => Err(locale::fill("option.mode", &[&value])),
+    }
+}
+/// Parses the level name as it is written in the menus or a slider value
+pub fn parse_level(value: &str) -> Result<Difficulty, String> {
+    let level = match value {
+        "stupid" => Level::Stupid,
+        "normal" => Level::Normal,
+        "smart" => Level::Smart,
+        "unbeatable" | "perfect" => Level::Perfect,
+        _ => match value.parse() {
+            Ok(slider) if (1..=Difficulty::MAX_SLIDER).contains(&slider) => {
+                return Ok(Difficulty::from_slider(slider))
+            }
+            _ => return Err(locale::fill("option.level", &[&value])),
+        },
+    };
+    Ok(Difficulty::from(level))
+}
+/// Parses x or o
+pub fn parse_player(value: &str) -> Result<Cell, String> {
+    match value {
+        "x" | "X" => Ok(Cell::X),
+        "o" | "O" => Ok(Cell::O),
+        _ => Err(locale::fill("option.player", &[&value])),
+    }
+}
+/// Parses who starts as it is written in the command line
+pub fn parse_start(value: &str) -> Result<Start, String> {
+    match value {
+        "x" | "X" => Ok(Start::X),
+        "o" | "O" => Ok(Start::O),
+        "alternate" => Ok(Start::Alternate),
+        "random" => Ok(Start::Random),
+        _ => Err(locale::fill("option.first", &[&value])),
+    }
+}
+/// Returns who starts as it is written in the command line
+pub fn start_name(start: Start) -> &'static str {
+    match start {
+        Start::X => "x",
+        Start::O => "o",
+        Start::Alternate => "alternate",
+        Start::Random => "random",
+    }
+}
+/// Returns the name of the mode as it is written in the command line
+pub fn mode_name(mode: Mode) -> &'static str {
+    match mode {
+        Mode::Person => "pvp",
+        Mode::Computer => "pvc",
+        Mode::Computers => "cvc",
+    }
+}
+fn parse_number(arg: &str, value: &str, min: u64) -> Result<u64, String> {
+    match value.parse() {
+        Ok(number) if number >= min => Ok(number),
+        _ => Err(locale::fill("option.number", &[&arg, &min])),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_every_option() {
+        let options = parse(&[
+            "--mode",
+            "cvc",
+            "--level",
+            "smart",
+            "--play-as",
+            "o",
+            "--seed",
+            "0",
+            "--games",
+            "3",
+            "--first",
+            "alternate",
+            "--best-of",
+            "5",
+            "--adapt",
+            "40",
+            "--delay",
+            "0",
+            "--name-x",
+            "Alice",
+            "--name-o",
+            "Bob",
+            "--mark-x",
+            "A",
+            "--mark-o",
+            "B",
+            "--lang",
+            "ar",
+            "--no-tui",
+            "--theme",
+            "ocean",
+            "--help",
+        ])
+        .unwrap();
+        assert!(options.mode == Some(Mode::Computers));
+        assert_eq!(options.level, Some(Difficulty::from(Level::Smart)));
+        assert_eq!(options.play_as, Some(Cell::O));
+        assert_eq!(options.seed, Some(0));
+        assert_eq!(options.games, Some(3));
+        assert!(options.start == Some(Start::Alternate));
+        assert_eq!(options.best_of, Some(5));
+        assert_eq!(options.target_win_rate, Some(40));
+        assert_eq!(options.delay, Some(0));
+        assert_eq!(options.name_x.as_deref(), Some("Alice"));
+        assert_eq!(options.name_o.as_deref(), Some("Bob"));
+        assert_eq!(options.mark_x.as_deref(), Some("A"));
+        assert_eq!(options.mark_o.as_deref(), Some("B"));
+        assert_eq!(options.language, locale::find("ar"));
+        assert_eq!(options.tui, Some(false));
+        assert_eq!(options.theme, theme::find("ocean"));
+        assert!(options.help);
+    }
+
+    #[test]
+    fn no_arguments_give_no_options() {
+        let options = parse(&[]).unwrap();
+        assert!(options.mode.is_none() && options.level.is_none() && options.tui.is_none());
+        assert!(!options.help);
+        assert_eq!(parse(&["--tui", "-h"]).unwrap().tui, Some(true));
+    }
+
+    #[test]
+    fn refuses_unknown_argument() {
+        assert_eq!(
+            parse(&["--colors", "on"]).err(),
+            Some("Unknown argument --colors!".to_string())
+        );
+        assert_eq!(
+            parse(&["play"]).err(),
+            Some("Unknown argument play!".to_string())
+        );
+        assert!(parse(&["--mode", "online"]).is_err());
+        assert!(parse(&["--play-as", "z"]).is_err());
+        assert!(parse(&["--first", "both"]).is_err());
+        assert!(parse(&["--lang", "xx"]).is_err());
+        assert!(parse(&["--theme", "neon"]).is_err());
+    }
+
+    #[test]
+    fn refuses_missing_value() {
+        assert_eq!(
+            parse(&["--mode"]).err(),
+            Some("--mode needs a value!".to_string())
+        );
+        assert!(parse(&["--tui", "--games"]).is_err());
+    }
+
+    #[test]
+    fn refuses_numbers_under_minimum() {
+        assert_eq!(
+            parse(&["--games", "0"]).err(),
+            Some("--games must be a number not less than 1!".to_string())
+        );
+        assert!(parse(&["--best-of", "0"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+        assert!(parse(&["--delay", "soon"]).is_err());
+        assert!(parse(&["--adapt", "101"]).is_err());
+        assert_eq!(parse(&["--best-of", "1"]).unwrap().best_of, Some(1));
+        assert_eq!(parse(&["--adapt", "0"]).unwrap().target_win_rate, Some(0));
+    }
+
+    #[test]
+    fn parses_levels_and_slider_values() {
+        assert_eq!(parse_level("6"), Ok(Difficulty::from_slider(6)));
+        assert_eq!(parse_level("smart"), Ok(Difficulty::from(Level::Smart)));
+        assert_eq!(parse_level("perfect"), parse_level("10"));
+        assert!(parse_level("11").is_err());
+        assert!(parse_level("0").is_err());
+        assert!(parse_level("clever").is_err());
+    }
+}
@@ -0,0 +1,184 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::locale;
+use crate::names;
+use std::sync::Mutex;
+
+/// Results of all the games played since the game started
+static SESSION: Mutex<Scoreboard> = Mutex::new(Scoreboard::new());
+
+/// Wins, draws and losses of one player
+struct Record {
+    name: String,
+    wins: u32,
+    draws: u32,
+    losses: u32,
+}
+
+/// Results of the games of each player, computers are named with their level
+/// so every level has its own record
+pub struct Scoreboard {
+    records: Vec<Record>,
+    games: u32,
+}
+impl Default for Scoreboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Scoreboard {
+    /// Creates new empty Scoreboard
+    pub const fn new() -> Scoreboard {
+        Scoreboard {
+            records: Vec::new(),
+            games: 0,
+        }
+    }
+    /// Adds a game between the two players, winner is the index of the winner
+    /// in players or None for a draw
+    pub fn add_game(&mut self, players: [&str; 2], winner: Option<usize>) {
+        self.games += 1;
+        for (i, name) in players.iter().enumerate() {
+            let record = self.record(name);
+            match winner {
+                None => record.draws += 1,
+                Some(winner) if winner == i => record.wins += 1,
+                Some(_) => record.losses += 1,
+            }
+        }
+    }
+    /// returns the number of games the player won
+    pub fn get_wins(&self, name: &str) -> u32 {
+        self.records
+            .iter()
+            .find(|record| record.name == name)
+            .map_or(0, |record| record.wins)
+    }
+    /// returns the number of games played
+    pub fn get_games(&self) -> u32 {
+        self.games
+    }
+    /// returns whether a player won more than half of a match of best_of games,
+    /// so the games left cannot change who wins it
+    pub fn is_decided(&self, best_of: u64) -> bool {
+        self.records
+            .iter()
+            .any(|record| u64::from(record.wins) > best_of / 2)
+    }
+    /// Returns one line for each player in the order they first played
+    pub fn lines(&self) -> Vec<String> {
+        let width = self
+            .records
+            .iter()
+            .map(|record| names::display_width(&record.name))
+            .max()
+            .unwrap_or(0);
+        self.records
+            .iter()
+            .map(|record| {
+                let score =
+                    locale::fill("score.line", &[&record.wins, &record.draws, &record.losses]);
+                format!("{}  {}", names::pad(&record.name, width), score)
+            })
+            .collect()
+    }
+    /// Returns the record of the player, adding it if it is not there
+    fn record(&mut self, name: &str) -> &mut Record {
+        let position = match self.records.iter().position(|record| record.name == name) {
+            Some(position) => position,
+            None => {
+                self.records.push(Record {
+                    name: name.to_string(),
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                });
+                self.records.len() - 1
+            }
+        };
+        &mut self.records[position]
+    }
+}
+
+/// Adds a game to the session scoreboard
+pub fn add_session_game(players: [&str; 2], winner: Option<usize>) {
+    session().add_game(players, winner);
+}
+/// Returns the number of games played in the session
+pub fn session_games() -> u32 {
+    session().get_games()
+}
+/// Returns the lines of the session scoreboard
+pub fn session_lines() -> Vec<String> {
+    session().lines()
+}
+/// Prints the session scoreboard or nothing if no game was played
+pub fn show_session(title: &str) {
+    let session = session();
+    if session.get_games() == 0 {
+        return;
+    }
+    println!(
+        "\n{}",
+        locale::fill("score.title", &[&title, &session.get_games()])
+    );
+    for line in session.lines() {
+        println!("    {}", line);
+    }
+}
+fn session() -> std::sync::MutexGuard<'static, Scoreboard> {
+    SESSION.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_wins_draws_and_losses() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.add_game(["Alice", "Bob"], Some(0));
+        scoreboard.add_game(["Bob", "Alice"], None);
+        scoreboard.add_game(["Bob", "Alice"], Some(0));
+        assert_eq!(scoreboard.get_games(), 3);
+        assert_eq!(scoreboard.get_wins("Alice"), 1);
+        assert_eq!(scoreboard.get_wins("Bob"), 1);
+        assert_eq!(scoreboard.get_wins("Carol"), 0);
+        let alice = &scoreboard.records[0];
+        assert_eq!((alice.wins, alice.draws, alice.losses), (1, 1, 1));
+    }
+
+    #[test]
+    fn lines_are_aligned_in_the_order_players_first_played() {
+        let mut scoreboard = Scoreboard::new();
+        assert!(scoreboard.lines().is_empty());
+        scoreboard.add_game(["Bob", "井字棋"], Some(1));
+        let lines = scoreboard.lines();
+        assert_eq!(
+            lines[0],
+            format!("Bob     {}", locale::fill("score.line", &[&0, &0, &1]))
+        );
+        assert_eq!(
+            lines[1],
+            format!("井字棋  {}", locale::fill("score.line", &[&1, &0, &0]))
+        );
+    }
+
+    #[test]
+    fn match_is_decided_by_a_majority_of_wins() {
+        let mut scoreboard = Scoreboard::new();
+        scoreboard.add_game(["Alice", "Bob"], Some(0));
+        scoreboard.add_game(["Alice", "Bob"], None);
+        assert!(!scoreboard.is_decided(3));
+        scoreboard.add_game(["Alice", "Bob"], Some(0));
+        assert!(scoreboard.is_decided(3));
+        assert!(!scoreboard.is_decided(4));
+        assert!(scoreboard.is_decided(1));
+    }
+}
@@ -0,0 +1,1219 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::config::{self, Config};
+use crate::game_core::notation;
+use crate::game_core::rand::Rng;
+use crate::game_core::{
+    play_game, Action, Cell, ComputerPlayer, Controller, Difficulty, GameStatus, Level, Move,
+    MoveError, Outcome, Player, Position, Search, ANALYZE_NODES,
+};
+use crate::locale;
+use crate::names;
+use crate::options::{self, Options};
+use crate::scoreboard::{self, Scoreboard};
+use crate::theme::{self, Highlights};
+use crate::tui::{self, Screen, TuiPlayer};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+/// Options chosen in the menus
+struct Settings {
+    mode: Mode,
+    /// the opponent of the human or player O when computers play each other
+    computer_player: ComputerPlayer,
+    /// player X when computers play each other
+    other_computer: ComputerPlayer,
+    /// milliseconds to wait before each move when computers play each other
+    delay: u64,
+    /// number of games computers play each other
+    games: u64,
+    /// number of games of a match with the computer or another person
+    best_of: u64,
+    /// percent of the games the human should win against the adaptive computer,
+    /// 0 keeps the level fixed
+    target_win_rate: u64,
+    /// level name and target the adaptive computer was resumed for from the history,
+    /// it keeps its state from match to match while they stay the same
+    adapted: Option<(String, u64)>,
+    /// who plays the first move
+    start: Start,
+    /// chooses the first player when it is random
+    rng: Rng,
+    /// whether humans play in the full screen terminal
+    tui: bool,
+    /// index of the colors theme in theme::THEMES
+    theme: usize,
+    /// index of the language in locale::LANGUAGES, None to follow the environment
+    language: Option<usize>,
+}
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Mode {
+    Computer,
+    Person,
+    Computers,
+}
+/// Who plays the first move of each game
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Start {
+    X,
+    O,
+    /// X and O take turns to start
+    Alternate,
+    Random,
+}
+/// Starts the game and communicate with the player
+pub fn run() {
+    let mut controller = Controller::new();
+    let mut settings = Settings {
+        mode: Mode::Computer,
+        computer_player: ComputerPlayer::new(),
+        other_computer: ComputerPlayer::new(),
+        delay: 0,
+        games: 1,
+        best_of: 1,
+        target_win_rate: 0,
+        adapted: None,
+        start: Start::X,
+        rng: Rng::from_time(),
+        tui: false,
+        theme: theme::DEFAULT_THEME,
+        language: None,
+    };
+    // the messages of the command line are in the language of the environment
+    locale::set(locale::from_env().unwrap_or(0));
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            println!("{}\n\n{}", error, locale::text("usage"));
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", locale::text("usage"));
+        return;
+    }
+    load_settings(&Config::load(), &mut settings);
+    let mut skip_menus = apply_options(&options, &mut settings);
+    theme::set(settings.theme);
+    locale::set(settings.language.or_else(locale::from_env).unwrap_or(0));
+    if let Err(error) = apply_names(&options) {
+        println!("{}\n\n{}", error, locale::text("usage"));
+        process::exit(2);
+    }
+    show_intro();
+    loop {
+        if skip_menus {
+            skip_menus = false;
+            controller.initialize();
+        } else {
+            input_mode(&mut controller, &mut settings);
+        }
+        save_settings(&settings);
+        adapt(&mut settings);
+        if settings.mode == Mode::Computers && settings.games > 1 {
+            play_tally(&mut controller, &mut settings);
+            continue;
+        }
+        play_match(&mut controller, &mut settings);
+    }
+}
+/// Plays the games of a match until a player wins most of them, the players
+/// take turns to start and the scoreboard is shown after each game.
+fn play_match(c: &mut Controller, s: &mut Settings) {
+    let names = player_names(s);
+    names::set_playing(Some(names.clone()));
+    let names = [names[0].as_str(), names[1].as_str()];
+    let best_of = match s.mode {
+        Mode::Computers => 1,
+        _ => s.best_of,
+    };
+    let mut match_score = Scoreboard::new();
+    for game in 0..best_of {
+        let first_player = choose_first_player(s);
+        c.set_first_player(first_player);
+        if best_of > 1 {
+            println!("\n{}", locale::fill("match.game", &[&(game + 1), &best_of]));
+        }
+        let status = play_one(c, s);
+        show_result(c, &status);
+        if status == GameStatus::InProgress {
+            break;
+        }
+        let winner = match status.get_winner() {
+            Cell::X => Some(0),
+            Cell::O => Some(1),
+            Cell::Empty => None,
+        };
+        if s.mode == Mode::Computer {
+            add_human_result(s, &status);
+        }
+        match_score.add_game(names, winner);
+        scoreboard::add_session_game(names, winner);
+        scoreboard::show_session(locale::text("score.board"));
+        if match_score.is_decided(best_of) {
+            break;
+        }
+    }
+    if best_of > 1 && match_score.get_games() > 0 {
+        show_match_result(&match_score, names);
+    }
+    names::set_playing(None);
+}
+/// Starts the adaptive mode of the computer when a target win rate is chosen against
+/// it, from where the last games in the same level and target left it in the history.
+/// The history is read again only when the level or the target changes, otherwise
+/// the computer goes on from where the last match left it.
+fn adapt(s: &mut Settings) {
+    if s.mode != Mode::Computer || s.target_win_rate == 0 {
+        s.computer_player.set_adaptive(None);
+        s.adapted = None;
+        return;
+    }
+    let level = difficulty_name(s.computer_player.get_difficulty());
+    let adapted = Some((level.clone(), s.target_win_rate));
+    if s.adapted == adapted && s.computer_player.get_adaptive().is_some() {
+        return;
+    }
+    s.adapted = adapted;
+    s.computer_player
+        .set_adaptive(Some(s.target_win_rate as f64 / 100.0));
+    let target = s.target_win_rate.to_string();
+    let (mut results, mut blunder) = (Vec::new(), None);
+    for line in config::load_history() {
+        // result, level, target and the blunder probability after the game
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (result, line_blunder) = match fields[..] {
+            [result, line_level, line_target, line_blunder]
+                if line_level == level && line_target == target =>
+            {
+                (result, line_blunder)
+            }
+            _ => continue,
+        };
+        let result = match result {
+            "win" => Outcome::Win,
+            "draw" => Outcome::Draw,
+            "loss" => Outcome::Loss,
+            _ => continue,
+        };
+        if let Ok(line_blunder) = line_blunder.parse::<f64>() {
+            results.push(result);
+            blunder = Some(line_blunder);
+        }
+    }
+    if let Some(blunder) = blunder {
+        s.computer_player.resume_adaptive(blunder, &results);
+    }
+}
+/// Lets the adaptive computer follow the result of the human and saves it in the history
+/// with the level, the target and the blunder probability it reached.
+fn add_human_result(s: &mut Settings, status: &GameStatus) {
+    if s.computer_player.get_adaptive().is_none() {
+        return;
+    }
+    let human = s.computer_player.get_opponent();
+    let (result, name) = match status.get_winner() {
+        Cell::Empty => (Outcome::Draw, "draw"),
+        winner if winner == human => (Outcome::Win, "win"),
+        _ => (Outcome::Loss, "loss"),
+    };
+    s.computer_player.add_result(result);
+    let line = format!(
+        "{} {} {} {}",
+        name,
+        difficulty_name(s.computer_player.get_difficulty()),
+        s.target_win_rate,
+        s.computer_player.get_blunder()
+    );
+    if let Err(error) = config::add_history(&line) {
+        println!("{}", locale::fill("history.failed", &[&error]));
+    }
+}
+/// Returns the player who starts the next game, alternate takes turns
+/// from X game after game of the session.
+fn choose_first_player(s: &mut Settings) -> Cell {
+    match s.start {
+        Start::X => Cell::X,
+        Start::O => Cell::O,
+        Start::Alternate if scoreboard::session_games().is_multiple_of(2) => Cell::X,
+        Start::Alternate => Cell::O,
+        Start::Random if s.rng.generate_range(0, 2) == 0 => Cell::X,
+        Start::Random => Cell::O,
+    }
+}
+/// Prints who won the match.
+fn show_match_result(score: &Scoreboard, names: [&str; 2]) {
+    let wins = [score.get_wins(names[0]), score.get_wins(names[1])];
+    if wins[0] == wins[1] {
+        println!("\n{}", locale::fill("match.draw", &[&wins[0], &wins[1]]));
+    } else {
+        let winner = if wins[0] > wins[1] { 0 } else { 1 };
+        let won = locale::fill(
+            "match.won",
+            &[&names[winner], &wins[winner], &wins[1 - winner]],
+        );
+        println!("\n{}", won);
+    }
+}
+/// Returns the names of player X and player O in the scoreboard.
+fn player_names(s: &Settings) -> [String; 2] {
+    let computer =
+        |key, cp: &ComputerPlayer| locale::fill(key, &[&level_text(cp.get_difficulty())]);
+    let you = |player| names::chosen_name(player).unwrap_or(locale::text("name.you").to_string());
+    match s.mode {
+        Mode::Computer if s.computer_player.get_player_turn() == Cell::X => {
+            [computer("name.computer", &s.computer_player), you(Cell::O)]
+        }
+        Mode::Computer => [you(Cell::X), computer("name.computer", &s.computer_player)],
+        Mode::Person => [names::name(Cell::X), names::name(Cell::O)],
+        Mode::Computers => [
+            computer("name.computer_x", &s.other_computer),
+            computer("name.computer_o", &s.computer_player),
+        ],
+    }
+}
+/// Plays one game of the mode and returns how it ended.
+fn play_one(c: &mut Controller, s: &mut Settings) -> GameStatus {
+    let screen = if s.tui && s.mode != Mode::Computers {
+        let screen = Screen::enter();
+        if screen.is_none() {
+            println!("{}", locale::text("tui.unavailable"));
+        }
+        screen
+    } else {
+        None
+    };
+    let full_screen = screen.is_some();
+    let computer_player = &mut s.computer_player;
+    let status = match s.mode {
+        Mode::Computer => {
+            // against the computer a take back covers its move and the player's one
+            let mut human = new_human(full_screen, 2);
+            if computer_player.get_player_turn() == Cell::X {
+                play_game(c, computer_player, human.as_mut())
+            } else {
+                play_game(c, human.as_mut(), computer_player)
+            }
+        }
+        Mode::Person => play_game(
+            c,
+            new_human(full_screen, 1).as_mut(),
+            new_human(full_screen, 1).as_mut(),
+        ),
+        Mode::Computers => play_game(
+            c,
+            &mut WatchedPlayer::new(&mut s.other_computer, s.delay),
+            &mut WatchedPlayer::new(computer_player, s.delay),
+        ),
+    };
+    if full_screen && status != GameStatus::InProgress {
+        let score = scoreboard::session_lines();
+        tui::draw(c, None, &score, locale::text("tui.game_over"));
+        tui::wait_key();
+    }
+    status
+}
+/// Returns a human player for the full screen terminal or the line by line one.
+fn new_human(full_screen: bool, steps: usize) -> Box<dyn Player> {
+    if full_screen {
+        Box::new(TuiPlayer::new(steps, scoreboard::session_lines()))
+    } else {
+        Box::new(HumanPlayer::new(steps))
+    }
+}
+/// Lets the computers play the chosen number of games and prints their results.
+fn play_tally(c: &mut Controller, s: &mut Settings) {
+    let names = player_names(s);
+    let names = [names[0].as_str(), names[1].as_str()];
+    let (mut x_wins, mut o_wins, mut draws) = (0, 0, 0);
+    for _ in 0..s.games {
+        let first_player = choose_first_player(s);
+        c.set_first_player(first_player);
+        let status = play_game(c, &mut s.other_computer, &mut s.computer_player);
+        let winner = match status.get_winner() {
+            Cell::X => {
+                x_wins += 1;
+                Some(0)
+            }
+            Cell::O => {
+                o_wins += 1;
+                Some(1)
+            }
+            Cell::Empty => {
+                draws += 1;
+                None
+            }
+        };
+        scoreboard::add_session_game(names, winner);
+    }
+    let x_level = level_text(s.other_computer.get_difficulty());
+    let o_level = level_text(s.computer_player.get_difficulty());
+    let tally = [
+        ("X", x_level, x_wins, o_wins),
+        ("O", o_level, o_wins, x_wins),
+    ];
+    for (player, level, wins, losses) in tally {
+        let line = locale::fill("tally.line", &[&player, &level, &wins, &draws, &losses]);
+        println!("{}", line);
+    }
+}
+/// Returns the name of the level as it is written in the config.
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Stupid => "stupid",
+        Level::Normal => "normal",
+        Level::Smart => "smart",
+        Level::Perfect => "unbeatable",
+    }
+}
+/// Returns the difficulty as it is written in the config: the name of its level
+/// or its slider value, the nearest one if it is not on the slider.
+fn difficulty_name(difficulty: Difficulty) -> String {
+    let value = difficulty.get_nearest_slider();
+    match Level::from_slider(value) {
+        Some(level) => level_name(level).to_string(),
+        None => value.to_string(),
+    }
+}
+/// Returns the name of the difficulty in the language of the messages.
+fn level_text(difficulty: Difficulty) -> String {
+    match difficulty.get_slider() {
+        Some(value) => match Level::from_slider(value) {
+            Some(level) => locale::text(&format!("level.{}", level_name(level))).to_string(),
+            None => locale::fill("level.number", &[&value, &Difficulty::MAX_SLIDER]),
+        },
+        None => locale::text("level.custom").to_string(),
+    }
+}
+/// Puts the last used option saved in the config in settings.
+fn load_settings(config: &Config, s: &mut Settings) {
+    if let Some(Ok(mode)) = config.get("mode").map(options::parse_mode) {
+        s.mode = mode;
+    }
+    if let Some(Ok(difficulty)) = config.get("level").map(options::parse_level) {
+        s.computer_player.set_difficulty(difficulty);
+    }
+    if let Some(Ok(difficulty)) = config.get("x_level").map(options::parse_level) {
+        s.other_computer.set_difficulty(difficulty);
+    }
+    if let Some(Ok(player)) = config.get("play_as").map(options::parse_player) {
+        s.computer_player.set_player_turn(player.opponent());
+    }
+    if let Some(Ok(games)) = config.get("games").map(str::parse) {
+        s.games = games;
+    }
+    if let Some(Ok(best_of)) = config.get("best_of").map(str::parse::<u64>) {
+        s.best_of = best_of.max(1);
+    }
+    if let Some(Ok(target)) = config.get("target_win_rate").map(str::parse::<u64>) {
+        s.target_win_rate = target.min(100);
+    }
+    if let Some(Ok(start)) = config.get("first").map(options::parse_start) {
+        s.start = start;
+    }
+    if let Some(Ok(delay)) = config.get("delay").map(str::parse) {
+        s.delay = delay;
+    }
+    if let Some(Ok(tui)) = config.get("tui").map(str::parse) {
+        s.tui = tui;
+    }
+    if let Some(theme) = config.get("theme").and_then(theme::find) {
+        s.theme = theme;
+    }
+    if let Some(language) = config.get("language").and_then(locale::find) {
+        s.language = Some(language);
+    }
+    for (player, key) in [(Cell::X, "x"), (Cell::O, "o")] {
+        if let Some(name) = config.get(&format!("name_{}", key)) {
+            let _ = names::set_name(player, name);
+        }
+        if let Some(mark) = config.get(&format!("mark_{}", key)) {
+            let _ = names::set_mark(player, mark);
+        }
+    }
+}
+/// Saves settings in the config to be the last used option of the next runs.
+fn save_settings(s: &Settings) {
+    let mut config = Config::load();
+    config.set("mode", options::mode_name(s.mode));
+    config.set(
+        "level",
+        &difficulty_name(s.computer_player.get_difficulty()),
+    );
+    config.set(
+        "x_level",
+        &difficulty_name(s.other_computer.get_difficulty()),
+    );
+    let play_as = s.computer_player.get_opponent();
+    config.set("play_as", &play_as.to_str().to_lowercase());
+    config.set("games", &s.games.to_string());
+    config.set("best_of", &s.best_of.to_string());
+    config.set("target_win_rate", &s.target_win_rate.to_string());
+    config.set("first", options::start_name(s.start));
+    config.set("delay", &s.delay.to_string());
+    config.set("tui", &s.tui.to_string());
+    config.set("theme", theme::THEMES[s.theme].name);
+    let language = s
+        .language
+        .map_or("", |language| locale::LANGUAGES[language].code);
+    config.set("language", language);
+    for (player, key) in [(Cell::X, "x"), (Cell::O, "o")] {
+        let name = names::chosen_name(player).unwrap_or_default();
+        config.set(&format!("name_{}", key), &name);
+        config.set(&format!("mark_{}", key), &names::mark(player));
+    }
+    if let Err(error) = config.save() {
+        println!("{}", locale::fill("config.failed", &[&error]));
+    }
+}
+/// Returns what the last used option means now.
+fn describe_settings(s: &Settings) -> String {
+    let x_level = level_text(s.other_computer.get_difficulty());
+    let o_level = level_text(s.computer_player.get_difficulty());
+    let description = match s.mode {
+        Mode::Computer if s.target_win_rate > 0 => locale::fill(
+            "describe.adaptive",
+            &[
+                &level_text(s.computer_player.get_difficulty()),
+                &s.computer_player.get_opponent().to_str(),
+                &s.target_win_rate,
+            ],
+        ),
+        Mode::Computer => locale::fill(
+            "describe.computer",
+            &[
+                &level_text(s.computer_player.get_difficulty()),
+                &s.computer_player.get_opponent().to_str(),
+            ],
+        ),
+        Mode::Person => locale::text("describe.person").to_string(),
+        Mode::Computers if s.games > 1 => {
+            locale::fill("describe.tally", &[&s.games, &x_level, &o_level])
+        }
+        Mode::Computers => locale::fill("describe.watch", &[&x_level, &o_level]),
+    };
+    let description = match s.start {
+        Start::X => description,
+        Start::O => locale::fill("describe.o_starts", &[&description]),
+        Start::Alternate => locale::fill("describe.alternate", &[&description]),
+        Start::Random => locale::fill("describe.random", &[&description]),
+    };
+    if s.mode != Mode::Computers && s.best_of > 1 {
+        locale::fill("describe.best_of", &[&description, &s.best_of])
+    } else {
+        description
+    }
+}
+/// Sets the names and marks given in the command line options, the saved ones
+/// they replace are dropped first so that the players can swap them.
+fn apply_names(options: &Options) -> Result<(), String> {
+    let given = [
+        (Cell::X, &options.name_x, &options.mark_x),
+        (Cell::O, &options.name_o, &options.mark_o),
+    ];
+    for (player, name, mark) in given {
+        if name.is_some() {
+            let _ = names::set_name(player, "");
+        }
+        if mark.is_some() {
+            let _ = names::set_mark(player, player.to_str());
+        }
+    }
+    for (player, name, mark) in given {
+        if let Some(name) = name {
+            names::set_name(player, name)?;
+        }
+        if let Some(mark) = mark {
+            names::set_mark(player, mark)?;
+        }
+    }
+    Ok(())
+}
+/// Puts the command line options in settings and returns whether the menus can be skipped.
+fn apply_options(options: &Options, s: &mut Settings) -> bool {
+    if let Some(seed) = options.seed {
+        s.computer_player.set_seed(seed);
+        s.other_computer.set_seed(seed.wrapping_add(1));
+        s.rng = Rng::new(seed.wrapping_add(2));
+    }
+    if let Some(difficulty) = options.level {
+        s.computer_player.set_difficulty(difficulty);
+        s.other_computer.set_difficulty(difficulty);
+    }
+    if let Some(player) = options.play_as {
+        s.computer_player.set_player_turn(player.opponent());
+    }
+    if let Some(games) = options.games {
+        s.games = games;
+    }
+    if let Some(best_of) = options.best_of {
+        s.best_of = best_of;
+    }
+    if let Some(target) = options.target_win_rate {
+        s.target_win_rate = target;
+    }
+    if let Some(start) = options.start {
+        s.start = start;
+    }
+    if let Some(delay) = options.delay {
+        s.delay = delay;
+    }
+    if let Some(tui) = options.tui {
+        s.tui = tui;
+    }
+    if let Some(theme) = options.theme {
+        s.theme = theme;
+    }
+    if let Some(language) = options.language {
+        s.language = Some(language);
+    }
+    match options.mode {
+        Some(mode) => {
+            s.mode = mode;
+            true
+        }
+        None => false,
+    }
+}
+/// Prints the session summary and exits the game.
+fn exit_game() -> ! {
+    scoreboard::show_session(locale::text("score.summary"));
+    process::exit(0);
+}
+/// Prints the prompt and returns the line the player types.
+/// Exits the game when there is nothing more to read.
+fn read_input(prompt: &str) -> String {
+    let mut input = String::new();
+    print!("{}", prompt);
+    io::stdout().flush().expect("flush failed!");
+
+    let read = io::stdin()
+        .read_line(&mut input)
+        .expect("Failed to read line");
+    if read == 0 {
+        println!();
+        exit_game();
+    }
+    input
+}
+/// Prints the result of the game.
+fn show_result(c: &Controller, status: &GameStatus) {
+    match status {
+        GameStatus::InProgress => {}
+        GameStatus::Win { .. } | GameStatus::Resigned { .. } => {
+            show_playground(c);
+            if let GameStatus::Resigned { player } = status {
+                println!(
+                    "{}",
+                    locale::fill("result.resigned", &[&names::name(*player)])
+                );
+            }
+            let player = status.get_winner();
+            let winner = theme::paint(&names::name(player), &[theme::mark_style(player)]);
+            println!("{}", locale::fill("result.wins", &[&winner]));
+            println!("{}", locale::text("result.game_over"));
+        }
+        GameStatus::Draw => {
+            show_playground(c);
+            println!("{}", locale::text("result.draw"));
+            println!("{}", locale::text("result.game_over"));
+        }
+    }
+}
+/// Inputs mode that player wants.
+fn input_mode(c: &mut Controller, s: &mut Settings) {
+    show_field("field.mode");
+    show_option(1, locale::text("mode.computer"));
+    show_option(2, locale::text("mode.person"));
+    show_option(3, &locale::fill("mode.last", &[&describe_settings(s)]));
+    show_option(4, locale::text("mode.watch"));
+    show_option(5, locale::text("mode.names"));
+    show_option(0, locale::text("mode.exit"));
+    c.initialize(); // initialize the game
+
+    let mode = read_field("field.mode");
+
+    let mode: i32 = match mode.trim().parse() {
+        Ok(num) => num,
+        Err(_) => {
+            println!("{}", locale::text("input.numbers"));
+            input_mode(c, s);
+            return;
+        }
+    };
+
+    match mode {
+        0 => exit_game(),
+        1 => {
+            s.mode = Mode::Computer;
+            input_cp_info(c, s);
+        }
+        2 => {
+            s.mode = Mode::Person;
+            input_match_info(s);
+        }
+        3 => {}
+        4 => {
+            s.mode = Mode::Computers;
+            input_cvc_info(c, s);
+        }
+        5 => {
+            input_identity(Cell::X);
+            input_identity(Cell::O);
+            save_settings(s);
+            input_mode(c, s);
+        }
+        _ => {
+            println!("{}", locale::text("input.numbers"));
+            input_mode(c, s);
+        }
+    }
+}
+/// Inputs the name and the mark of player, typing nothing keeps them.
+fn input_identity(player: Cell) {
+    let name = names::name(player);
+    println!("\n{}", locale::fill("name.prompt", &[&name]));
+    loop {
+        let name = read_field("field.name");
+        if name.trim().is_empty() {
+            break;
+        }
+        match names::set_name(player, &name) {
+            Ok(()) => break,
+            Err(error) => println!("{}", error),
+        }
+    }
+    let (name, mark) = (names::name(player), names::mark(player));
+    println!("\n{}", locale::fill("mark.prompt", &[&name, &mark]));
+    loop {
+        let mark = read_field("field.mark");
+        if mark.trim().is_empty() {
+            break;
+        }
+        match names::set_mark(player, &mark) {
+            Ok(()) => break,
+            Err(error) => println!("{}", error),
+        }
+    }
+}
+/// Prints the title of the menu of the field with this message key.
+fn show_field(field: &str) {
+    println!("\n{}", locale::fill("field.type", &[&locale::text(field)]));
+}
+/// Prints one option of a menu.
+fn show_option(number: u32, text: &str) {
+    println!("    {} => {}", number, text);
+}
+/// Prints the name of the field with this message key and returns the line the player types.
+fn read_field(field: &str) -> String {
+    read_input(&format!("{}: ", locale::text(field)))
+}
+/// Inputs a difficulty on the slider, where the levels are some of the values,
+/// and returns it or None to go back to last step.
+fn input_level(field: &str) -> Option<Difficulty> {
+    show_field(field);
+    show_option(0, locale::text("menu.back"));
+    for value in 1..=Difficulty::MAX_SLIDER {
+        let difficulty = Difficulty::from_slider(value);
+        show_option(
+            value,
+            &locale::fill("level.option", &[&level_text(difficulty)]),
+        );
+    }
+    loop {
+        let level = read_field(field);
+
+        let level: u32 = match level.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        };
+
+        match level {
+            0 => return None,
+            1..=Difficulty::MAX_SLIDER => return Some(Difficulty::from_slider(level)),
+            _ => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        }
+    }
+}
+/// Inputs a number that is at least min.
+fn input_number(field: &str, min: u64) -> u64 {
+    loop {
+        let number = read_field(field);
+
+        match number.trim().parse() {
+            Ok(num) if num >= min => return num,
+            _ => println!("{}", locale::text("input.numbers")),
+        }
+    }
+}
+fn input_cp_info(c: &mut Controller, s: &mut Settings) {
+    match input_level("field.level") {
+        Some(difficulty) => s.computer_player.set_difficulty(difficulty),
+        None => {
+            input_mode(c, s);
+            return;
+        }
+    }
+    show_field("field.player");
+    show_option(0, locale::text("menu.back"));
+    show_option(1, locale::text("player.x"));
+    show_option(2, locale::text("player.o"));
+    loop {
+        let player_opponent = read_field("field.player");
+
+        let player_opponent: i32 = match player_opponent.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        };
+
+        match player_opponent {
+            0 => {
+                input_cp_info(c, s);
+                return;
+            }
+            1 => s.computer_player.set_player_turn(Cell::O),
+            2 => s.computer_player.set_player_turn(Cell::X),
+            _ => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        }
+        break;
+    }
+    println!("\n{}", locale::text("target.prompt"));
+    s.target_win_rate = loop {
+        match input_number("field.target", 0) {
+            target if target <= 100 => break target,
+            _ => println!("{}", locale::text("input.numbers")),
+        }
+    };
+    input_match_info(s);
+}
+/// Inputs who starts and the number of games of the match.
+fn input_match_info(s: &mut Settings) {
+    show_field("field.first");
+    show_option(1, locale::text("first.x"));
+    show_option(2, locale::text("first.o"));
+    show_option(3, locale::text("first.alternate"));
+    show_option(4, locale::text("first.random"));
+    loop {
+        let start: i32 = match read_field("field.first").trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        };
+        s.start = match start {
+            1 => Start::X,
+            2 => Start::O,
+            3 => Start::Alternate,
+            4 => Start::Random,
+            _ => {
+                println!("{}", locale::text("input.numbers"));
+                continue;
+            }
+        };
+        break;
+    }
+    println!("\n{}", locale::text("match.prompt"));
+    s.best_of = input_number("field.match", 1);
+}
+fn input_cvc_info(c: &mut Controller, s: &mut Settings) {
+    match input_level("field.x_level") {
+        Some(difficulty) => s.other_computer.set_difficulty(difficulty),
+        None => {
+            input_mode(c, s);
+            return;
+        }
+    }
+    match input_level("field.o_level") {
+        Some(difficulty) => s.computer_player.set_difficulty(difficulty),
+        None => {
+            input_cvc_info(c, s);
+            return;
+        }
+    }
+    println!("\n{}", locale::text("games.prompt"));
+    s.games = input_number("field.games", 1);
+    if s.games == 1 {
+        println!("\n{}", locale::text("delay.prompt"));
+        s.delay = input_number("field.delay", 0);
+    }
+}
+/// Player that types its moves in the terminal
+struct HumanPlayer {
+    /// number of moves taken back by undo and played again by redo
+    steps: usize,
+    /// hint or analysis shown with the next playground
+    overlay: Option<Overlay>,
+}
+impl HumanPlayer {
+    fn new(steps: usize) -> HumanPlayer {
+        HumanPlayer {
+            steps,
+            overlay: None,
+        }
+    }
+}
+/// Labels written in free cells of the playground and the text printed after it
+struct Overlay {
+    labels: Vec<((usize, usize), String)>,
+    /// location painted as a suggested move
+    suggestion: Option<(usize, usize)>,
+    text: String,
+}
+impl Player for HumanPlayer {
+    fn choose_move(&mut self, position: &Position) -> Move {
+        let controller = Controller::from(position);
+        loop {
+            if let Some(Action::Play(m)) = input_play(&controller, 0, &mut self.overlay) {
+                return m;
+            }
+        }
+    }
+    fn choose_action(&mut self, controller: &Controller) -> Action {
+        loop {
+            if let Some(action) = input_play(controller, self.steps, &mut self.overlay) {
+                return action;
+            }
+        }
+    }
+    fn move_rejected(&mut self, _m: Move, error: MoveError) {
+        println!("{}", locale::error_text(error));
+    }
+}
+/// Inputs player choice and returns it or None if it is not valid or it is a command
+/// that does not change the game. Undo and redo take back and play again steps moves.
+/// The hint or analysis asked for is shown on the playground before the next choice.
+fn input_play(c: &Controller, steps: usize, overlay: &mut Option<Overlay>) -> Option<Action> {
+    match overlay.take() {
+        Some(overlay) => {
+            print_playground(c, &overlay.labels, overlay.suggestion);
+            println!("{}", overlay.text);
+        }
+        None => show_playground(c),
+    }
+    let choice = read_input(&format!("{}: ", names::name(c.get_player_turn())));
+    let choice = choice.trim();
+    let (command, argument) = match choice.split_once(' ') {
+        Some((command, argument)) => (command, argument.trim()),
+        None => (choice, ""),
+    };
+
+    match command {
+        "help" | "?" => {
+            show_commands();
+            None
+        }
+        "undo" | "u" => {
+            if steps == 0 || c.get_history().len() < steps {
+                println!("{}", locale::text("play.nothing_undo"));
+                return None;
+            }
+            Some(Action::Undo(steps))
+        }
+        "redo" | "r" => {
+            if steps == 0 || c.get_redo_count() < steps {
+                println!("{}", locale::text("play.nothing_redo"));
+                return None;
+            }
+            Some(Action::Redo(steps))
+        }
+        "hint" => {
+            *overlay = find_hint(c).map(|(location, text)| Overlay {
+                labels: vec![(location, "*".to_string())],
+                suggestion: Some(location),
+                text,
+            });
+            None
+        }
+        "analyze" => {
+            *overlay = Some(analysis(c));
+            None
+        }
+        "save" if !argument.is_empty() => {
+            match fs::write(argument, notation::write_game(c)) {
+                Ok(()) => println!("{}", locale::fill("play.saved", &[&argument])),
+                Err(error) => println!("{}", locale::fill("play.save_failed", &[&error])),
+            }
+            None
+        }
+        "load" if !argument.is_empty() => {
+            match fs::read_to_string(argument)
+                .map_err(|error| error.to_string())
+                .and_then(|text| {
+                    notation::read_game(&text).map_err(|error| locale::read_error_text(&error))
+                }) {
+                Ok(loaded) => Some(Action::Load(loaded)),
+                Err(error) => {
+                    println!("{}", locale::fill("play.load_failed", &[&error]));
+                    None
+                }
+            }
+        }
+        "save" | "load" => {
+            println!("{}", locale::fill("play.file_name", &[&command]));
+            None
+        }
+        "resign" => Some(Action::Resign),
+        "new" => Some(Action::New),
+        "menu" | "quit" | "0" => Some(Action::Quit),
+        _ => match notation::parse_location(choice, c.get_width(), c.get_height()) {
+            Some((row, column)) => Some(Action::Play(Move::new(c.get_player_turn(), row, column))),
+            None => {
+                println!("{}", locale::text("play.unknown"));
+                None
+            }
+        },
+    }
+}
+/// Prints the commands that can be typed instead of a location.
+fn show_commands() {
+    println!("{}", locale::text("commands.intro"));
+    let commands = [
+        ("help", "commands.help"),
+        ("undo (or u)", "commands.undo"),
+        ("redo (or r)", "commands.redo"),
+        ("hint", "commands.hint"),
+        ("analyze", "commands.analyze"),
+        ("save <file>", "commands.save"),
+        ("load <file>", "commands.load"),
+        ("resign", "commands.resign"),
+        ("new", "commands.new"),
+        ("menu or quit", "commands.menu"),
+    ];
+    for (command, key) in commands {
+        println!("    {:<13} => {}", command, locale::text(key));
+    }
+}
+/// Returns the best location for the player whose turn it is and the description
+/// of what it leads to, or None if there is no free cell.
+pub(crate) fn find_hint(c: &Controller) -> Option<((usize, usize), String)> {
+    let position = Position::from(c);
+    let mut search = Search::new();
+    search.limit_nodes(&position, ANALYZE_NODES);
+    let (index, score) = search.best_move(&position)?;
+    let (row, column) = position.location(index);
+    let location = notation::format_location(row, column);
+    let text = match (Outcome::from_score(score), search.get_depth()) {
+        ((Outcome::Win, 1), _) => locale::fill("hint.win_now", &[&location]),
+        ((Outcome::Win, plies), _) => locale::fill("hint.win", &[&location, &plies]),
+        ((Outcome::Draw, _), None) => locale::fill("hint.draw", &[&location]),
+        ((Outcome::Draw, _), Some(depth)) => locale::fill("hint.ahead", &[&location, &depth]),
+        ((Outcome::Loss, plies), _) => locale::fill("hint.loss", &[&location, &plies]),
+    };
+    Some(((row, column), text))
+}
+/// Returns the outcome of every free cell as a label like W3, D or L2
+/// with the legend of the labels.
+fn analysis(c: &Controller) -> Overlay {
+    let position = Position::from(c);
+    let mut search = Search::new();
+    search.limit_nodes(&position, ANALYZE_NODES);
+    let depth = search.get_depth();
+    let labels = search
+        .analyze(&position)
+        .into_iter()
+        .map(|(m, outcome, plies)| {
+            // the labels stay latin to keep the playground left to right
+            let label = match outcome {
+                Some(Outcome::Win) => format!("W{}", plies),
+                Some(Outcome::Draw) => "D".to_string(),
+                Some(Outcome::Loss) => format!("L{}", plies),
+                None => "?".to_string(),
+            };
+            ((m.row, m.column), label)
+        })
+        .collect();
+    let text = match depth {
+        None => locale::text("analyze.legend").to_string(),
+        Some(depth) => locale::fill("analyze.limited", &[&depth]),
+    };
+    Overlay {
+        labels,
+        suggestion: None,
+        text,
+    }
+}
+/// Computer player whose moves are shown one after another
+struct WatchedPlayer<'a> {
+    computer_player: &'a mut ComputerPlayer,
+    /// milliseconds to wait before each move
+    delay: u64,
+}
+impl<'a> WatchedPlayer<'a> {
+    fn new(computer_player: &'a mut ComputerPlayer, delay: u64) -> WatchedPlayer<'a> {
+        WatchedPlayer {
+            computer_player,
+            delay,
+        }
+    }
+}
+impl Player for WatchedPlayer<'_> {
+    fn choose_move(&mut self, position: &Position) -> Move {
+        show_playground(&Controller::from(position));
+        print!("{}: ", names::name(position.get_player_turn()));
+        io::stdout().flush().expect("flush failed!");
+        thread::sleep(Duration::from_millis(self.delay));
+        let m = self.computer_player.choose_move(position);
+        println!("{}", notation::format_location(m.row, m.column));
+        m
+    }
+}
+/// Prints the playground.
+pub fn show_playground(c: &Controller) {
+    print_playground(c, &[], None);
+}
+/// Prints the playground with labels instead of the numbers of some free cells
+/// and the suggested location painted.
+fn print_playground(
+    c: &Controller,
+    labels: &[((usize, usize), String)],
+    suggestion: Option<(usize, usize)>,
+) {
+    let (width, height) = (c.get_width(), c.get_height());
+    let cell_width = (width * height)
+        .to_string()
+        .len()
+        .max(notation::row_letters(width).len())
+        .max(names::mark_width())
+        .max(
+            labels
+                .iter()
+                .map(|(_, label)| label.len())
+                .max()
+                .unwrap_or(0),
+        );
+    let label_width = height.to_string().len();
+    let margin = " ".repeat(label_width + 1);
+    let separator = format!("{}{}", margin, "-".repeat(width * (cell_width + 3) + 1));
+    let mut highlights = Highlights::of(c);
+    highlights.suggestion = suggestion;
+    let mut line = margin.clone();
+    for row in 1..=width {
+        line.push_str(&format!(
+            "  {:<w$} ",
+            notation::row_letters(row),
+            w = cell_width
+        ));
+    }
+    println!("{}", locale::left_to_right(&line));
+    println!("{}", locale::left_to_right(&separator));
+    for column in 1..=height {
+        let mut line = format!("{:>w$} | ", column, w = label_width);
+        for row in 1..=width {
+            let label = labels
+                .iter()
+                .find(|(location, _)| *location == (row, column));
+            let text = if c.get_content(row, column) != Cell::Empty {
+                names::pad(&names::mark(c.get_content(row, column)), cell_width)
+            } else if let Some((_, label)) = label {
+                format!("{:<w$}", label, w = cell_width)
+            } else {
+                format!("{:<w$}", (column - 1) * width + row, w = cell_width)
+            };
+            line.push_str(&format!(
+                "{} | ",
+                highlights.paint_cell(c, row, column, &text)
+            ));
+        }
+        println!("{}", locale::left_to_right(&line));
+        println!("{}", locale::left_to_right(&separator));
+    }
+}
+/// Prints how to play the game.
+pub fn show_intro() {
+    println!("{}\n", locale::text("intro.copyright"));
+    println!("{}", locale::text("intro.hello"));
+    println!("{}", locale::text("intro.cells"));
+    println!("{}", locale::text("intro.help"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a 3x3 game after X and O play the (row, column) locations in turn
+    fn game(moves: &[(usize, usize)]) -> Controller {
+        let mut controller = Controller::new();
+        for &(row, column) in moves.iter() {
+            let player = controller.get_player_turn();
+            controller.try_play(Move::new(player, row, column)).unwrap();
+        }
+        controller
+    }
+
+    #[test]
+    fn hint_takes_the_win() {
+        // X: a1, b1  O: a2, b2
+        let hint = find_hint(&game(&[(1, 1), (1, 2), (2, 1), (2, 2)]));
+        assert_eq!(hint, Some(((3, 1), "Hint: c1 wins now.".to_string())));
+    }
+
+    #[test]
+    fn hint_tells_the_draw() {
+        let hint = find_hint(&game(&[]));
+        assert_eq!(hint.map(|(_, text)| text.ends_with("draws.")), Some(true));
+        // X: a1, c3  O: b2
+        let hint = find_hint(&game(&[(1, 1), (2, 2), (3, 3)]));
+        assert_eq!(hint.map(|(_, text)| text.ends_with("draws.")), Some(true));
+    }
+
+    #[test]
+    fn hint_tells_the_loss() {
+        // X: a1, c3, a3  O: c1, c2 and X has more lines to finish than O can block
+        let hint = find_hint(&game(&[(1, 1), (3, 1), (3, 3), (3, 2), (1, 3)]));
+        assert_eq!(
+            hint.map(|(_, text)| text.contains("loses in 2")),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn hint_looks_ahead_on_big_playgrounds() {
+        let hint = find_hint(&Controller::with_size(15, 15, 5));
+        assert_eq!(
+            hint,
+            Some((
+                (8, 8),
+                "Hint: h8 is the best move looking 2 moves ahead.".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn full_playground_has_no_hint() {
+        let moves = [
+            (1, 1),
+            (2, 1),
+            (3, 1),
+            (2, 2),
+            (1, 2),
+            (3, 2),
+            (2, 3),
+            (1, 3),
+            (3, 3),
+        ];
+        assert_eq!(find_hint(&game(&moves)), None);
+    }
+}
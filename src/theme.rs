@@ -0,0 +1,216 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Cell, Controller, GameStatus};
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// ANSI SGR parameters used to color the playground, empty strings keep the text plain
+pub struct Theme {
+    pub name: &'static str,
+    pub x: &'static str,
+    pub o: &'static str,
+    /// numbers of the empty cells
+    pub hint: &'static str,
+    /// added to the cell of the last move
+    pub last_move: &'static str,
+    /// added to the cells of the winning line
+    pub win: &'static str,
+    /// added to the cell suggested by a hint
+    pub suggestion: &'static str,
+}
+
+/// All themes, the first one is the plain output
+pub const THEMES: [Theme; 4] = [
+    Theme {
+        name: "plain",
+        x: "",
+        o: "",
+        hint: "",
+        last_move: "",
+        win: "",
+        suggestion: "",
+    },
+    Theme {
+        name: "classic",
+        x: "1;31",
+        o: "1;34",
+        hint: "2",
+        last_move: "4",
+        win: "7",
+        suggestion: "1;32",
+    },
+    Theme {
+        name: "ocean",
+        x: "1;36",
+        o: "1;33",
+        hint: "2;34",
+        last_move: "4",
+        win: "7",
+        suggestion: "1;35",
+    },
+    Theme {
+        name: "contrast",
+        x: "1;97;41",
+        o: "1;97;44",
+        hint: "90",
+        last_move: "4",
+        win: "1;7",
+        suggestion: "1;97;42",
+    },
+];
+/// Theme used when no theme is chosen
+pub const DEFAULT_THEME: usize = 1;
+
+/// Index of the chosen theme in THEMES
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the index of the theme with this name
+pub fn find(name: &str) -> Option<usize> {
+    THEMES.iter().position(|theme| theme.name == name)
+}
+/// Chooses the theme used from now on
+///
+/// The plain theme is used instead when NO_COLOR is set or the output is not a terminal.
+pub fn set(index: usize) {
+    let colors =
+        env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()) && io::stdout().is_terminal();
+    let index = if colors && index < THEMES.len() {
+        index
+    } else {
+        0
+    };
+    CURRENT.store(index, Ordering::Relaxed);
+}
+/// Returns the theme used now
+pub fn current() -> &'static Theme {
+    &THEMES[CURRENT.load(Ordering::Relaxed)]
+}
+/// Returns text wrapped in the SGR parameters of styles, skipping the empty ones
+pub fn paint(text: &str, styles: &[&str]) -> String {
+    let styles: Vec<&str> = styles
+        .iter()
+        .copied()
+        .filter(|style| !style.is_empty())
+        .collect();
+    if styles.is_empty() {
+        return text.to_string();
+    }
+    format!("\x1b[{}m{}\x1b[0m", styles.join(";"), text)
+}
+/// Returns the style of the player mark
+pub fn mark_style(player: Cell) -> &'static str {
+    current().mark_style(player)
+}
+impl Theme {
+    /// returns the style of the player mark in this theme
+    fn mark_style(&self, player: Cell) -> &'static str {
+        match player {
+            Cell::X => self.x,
+            Cell::O => self.o,
+            Cell::Empty => self.hint,
+        }
+    }
+}
+
+/// Cells that are painted differently from the other cells of their content
+pub struct Highlights {
+    last_move: Option<(usize, usize)>,
+    winning_line: Vec<(usize, usize)>,
+    /// cell suggested by a hint
+    pub suggestion: Option<(usize, usize)>,
+    /// cell shown in reverse video whatever the theme is
+    pub cursor: Option<(usize, usize)>,
+}
+impl Highlights {
+    /// Returns the last move and the winning line of the game if there is one
+    pub fn of(c: &Controller) -> Highlights {
+        Highlights {
+            last_move: c.get_history().last().copied(),
+            winning_line: match c.status() {
+                GameStatus::Win { line, .. } => line,
+                _ => Vec::new(),
+            },
+            suggestion: None,
+            cursor: None,
+        }
+    }
+    /// Returns text painted as the cell with the (row, column) location of c
+    pub fn paint_cell(&self, c: &Controller, row: usize, column: usize, text: &str) -> String {
+        self.paint_cell_in(current(), c, row, column, text)
+    }
+    /// Returns text painted as the cell with the (row, column) location of c in theme
+    fn paint_cell_in(
+        &self,
+        theme: &Theme,
+        c: &Controller,
+        row: usize,
+        column: usize,
+        text: &str,
+    ) -> String {
+        let mut styles = vec![theme.mark_style(c.get_content(row, column))];
+        if self.last_move == Some((row, column)) {
+            styles.push(theme.last_move);
+        }
+        if self.winning_line.contains(&(row, column)) {
+            styles.push(theme.win);
+        }
+        if self.suggestion == Some((row, column)) {
+            styles.push(theme.suggestion);
+        }
+        if self.cursor == Some((row, column)) {
+            styles.push("7");
+        }
+        paint(text, &styles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_themes_by_name() {
+        assert_eq!(find("plain"), Some(0));
+        assert_eq!(find("classic"), Some(DEFAULT_THEME));
+        assert_eq!(find("contrast"), Some(3));
+        assert_eq!(find("Classic"), None);
+        assert_eq!(find(""), None);
+    }
+
+    #[test]
+    fn paint_skips_empty_styles() {
+        assert_eq!(paint("X", &[]), "X");
+        assert_eq!(paint("X", &["", ""]), "X");
+        assert_eq!(paint("X", &["1;31", "", "4"]), "\x1b[1;31;4mX\x1b[0m");
+    }
+
+    #[test]
+    fn highlights_last_move_winning_line_and_suggestion() {
+        let classic = &THEMES[DEFAULT_THEME];
+        let mut c = Controller::new();
+        for (row, column) in [(1, 1), (1, 2), (2, 1), (2, 2)] {
+            c.play_turn(row, column);
+        }
+        let mut highlights = Highlights::of(&c);
+        highlights.suggestion = Some((3, 1));
+        let paint_cell = |h: &Highlights, c: &Controller, row, column| {
+            h.paint_cell_in(classic, c, row, column, "x")
+        };
+        assert_eq!(paint_cell(&highlights, &c, 2, 2), "\x1b[1;34;4mx\x1b[0m");
+        assert_eq!(paint_cell(&highlights, &c, 1, 2), "\x1b[1;34mx\x1b[0m");
+        assert_eq!(paint_cell(&highlights, &c, 3, 1), "\x1b[2;1;32mx\x1b[0m");
+        c.play_turn(3, 1);
+        let highlights = Highlights::of(&c);
+        assert_eq!(paint_cell(&highlights, &c, 3, 1), "\x1b[1;31;4;7mx\x1b[0m");
+        assert_eq!(paint_cell(&highlights, &c, 1, 1), "\x1b[1;31;7mx\x1b[0m");
+        assert_eq!(paint_cell(&highlights, &c, 2, 2), "\x1b[1;34mx\x1b[0m");
+        assert_eq!(paint_cell(&highlights, &c, 3, 3), "\x1b[2mx\x1b[0m");
+    }
+}
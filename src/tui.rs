@@ -0,0 +1,251 @@
+/*
+This file is part of Tic-Tac-Toe game.
+Copyright (C) 2020 Ahmad Amr Siam
+This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at http://mozilla.org/MPL/2.0/.
+*/
+
+use crate::game_core::{Action, Cell, Controller, Move, MoveError, Player, Position};
+use crate::locale;
+use crate::names;
+use crate::terminal;
+use crate::theme::Highlights;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+/// Terminal switched to raw mode and the alternate screen, restored when dropped
+pub struct Screen {
+    /// stty settings before entering raw mode
+    saved: String,
+}
+impl Screen {
+    /// Switches the terminal to full screen and returns None if it is not possible
+    pub fn enter() -> Option<Screen> {
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return None;
+        }
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush().expect("flush failed!");
+        Some(Screen {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+impl Drop for Screen {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        io::stdout().flush().expect("flush failed!");
+        stty(&[self.saved.as_str()]);
+    }
+}
+/// Runs stty on the terminal and returns its output or None if it fails
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Char(char),
+    Quit,
+}
+/// Waits for a key press and returns it
+fn read_key() -> Key {
+    let mut stdin = io::stdin();
+    let mut byte = [0];
+    loop {
+        if stdin.read(&mut byte).unwrap_or(0) == 0 {
+            return Key::Quit;
+        }
+        match byte[0] {
+            b'\r' | b'\n' | b' ' => return Key::Enter,
+            // Ctrl-C and Ctrl-D
+            3 | 4 => return Key::Quit,
+            0x1b => match read_sequence(&mut stdin) {
+                // a lone Esc is a key that does nothing but end wait_key
+                None => return Key::Char('\x1b'),
+                Some([b'[', b'A']) => return Key::Up,
+                Some([b'[', b'B']) => return Key::Down,
+                Some([b'[', b'C']) => return Key::Right,
+                Some([b'[', b'D']) => return Key::Left,
+                Some(_) => continue,
+            },
+            b'k' => return Key::Up,
+            b'j' => return Key::Down,
+            b'l' => return Key::Right,
+            b'h' => return Key::Left,
+            byte if byte.is_ascii() => return Key::Char(byte as char),
+            _ => continue,
+        }
+    }
+}
+/// Reads the two bytes that follow Esc in the escape sequence of a key,
+/// returns None if they do not come within a tenth of a second
+fn read_sequence(stdin: &mut io::Stdin) -> Option<[u8; 2]> {
+    stty(&["min", "0", "time", "1"]);
+    let mut sequence = [0; 2];
+    let mut count = 0;
+    while count < sequence.len() {
+        match stdin.read(&mut sequence[count..]) {
+            Ok(0) | Err(_) => break,
+            Ok(read) => count += read,
+        }
+    }
+    stty(&["min", "1", "time", "0"]);
+    (count == sequence.len()).then_some(sequence)
+}
+/// Waits until any key is pressed
+pub fn wait_key() {
+    read_key();
+}
+
+/// Redraws the whole screen in place: the playground with the highlighted
+/// cursor cell and the turn, score and status panes
+pub fn draw(c: &Controller, cursor: Option<(usize, usize)>, score: &[String], status: &str) {
+    let mut lines = vec![locale::text("tui.title").to_string(), String::new()];
+    let cell_width = names::mark_width();
+    let separator = "-".repeat(c.get_width() * (cell_width + 3) + 1);
+    let mut highlights = Highlights::of(c);
+    highlights.cursor = cursor;
+    let separator = locale::left_to_right(&separator);
+    lines.push(separator.clone());
+    for column in 1..=c.get_height() {
+        let mut line = String::from("|");
+        for row in 1..=c.get_width() {
+            let content = match c.get_content(row, column) {
+                Cell::Empty => String::new(),
+                player => names::mark(player),
+            };
+            let content = format!(" {} ", names::pad(&content, cell_width));
+            line.push_str(&highlights.paint_cell(c, row, column, &content));
+            line.push('|');
+        }
+        lines.push(locale::left_to_right(&line));
+        lines.push(separator.clone());
+    }
+    lines.push(String::new());
+    // the panes are aligned after the longest label
+    let labels = ["tui.turn", "tui.score", "tui.status"].map(locale::text);
+    let label_width = labels.iter().map(|label| names::display_width(label)).max();
+    let label_width = label_width.unwrap_or(0) + 1;
+    let [turn, score_label, status_label] = labels.map(|label| names::pad(label, label_width));
+    lines.push(format!("{}{}", turn, names::name(c.get_player_turn())));
+    match score.split_first() {
+        Some((first, rest)) => {
+            lines.push(format!("{}{}", score_label, first));
+            let margin = " ".repeat(label_width);
+            lines.extend(rest.iter().map(|line| format!("{}{}", margin, line)));
+        }
+        None => lines.push(format!("{}{}", score_label, locale::text("score.empty"))),
+    }
+    lines.push(format!("{}{}", status_label, status));
+    lines.push(String::new());
+    lines.push(locale::text("tui.keys").to_string());
+
+    // every line clears what is left of the previous frame after it
+    print!("\x1b[H{}\x1b[K\x1b[J", lines.join("\x1b[K\r\n"));
+    io::stdout().flush().expect("flush failed!");
+}
+
+/// Player that moves a cursor on the full screen playground to choose its moves
+pub struct TuiPlayer {
+    /// number of moves taken back by undo and played again by redo
+    steps: usize,
+    /// (row, column) location of the highlighted cell
+    cursor: (usize, usize),
+    /// lines of the session scoreboard
+    score: Vec<String>,
+    message: String,
+}
+impl TuiPlayer {
+    pub fn new(steps: usize, score: Vec<String>) -> TuiPlayer {
+        TuiPlayer {
+            steps,
+            cursor: (0, 0),
+            score,
+            message: String::new(),
+        }
+    }
+    /// Puts the cursor in the playground, starting from its center
+    fn place_cursor(&mut self, c: &Controller) {
+        let (row, column) = self.cursor;
+        if row < 1 || column < 1 || row > c.get_width() || column > c.get_height() {
+            self.cursor = (c.get_width().div_ceil(2), c.get_height().div_ceil(2));
+        }
+    }
+}
+impl Player for TuiPlayer {
+    fn choose_move(&mut self, position: &Position) -> Move {
+        let controller = Controller::from(position);
+        let steps = self.steps;
+        self.steps = 0;
+        let m = loop {
+            if let Action::Play(m) = self.choose_action(&controller) {
+                break m;
+            }
+        };
+        self.steps = steps;
+        m
+    }
+    fn choose_action(&mut self, c: &Controller) -> Action {
+        self.place_cursor(c);
+        loop {
+            let status = if self.message.is_empty() {
+                locale::text("tui.choose")
+            } else {
+                self.message.as_str()
+            };
+            draw(c, Some(self.cursor), &self.score, status);
+            let key = read_key();
+            self.message.clear();
+            let (row, column) = self.cursor;
+            match key {
+                Key::Up => self.cursor.1 = (column - 1).max(1),
+                Key::Down => self.cursor.1 = (column + 1).min(c.get_height()),
+                Key::Left => self.cursor.0 = (row - 1).max(1),
+                Key::Right => self.cursor.0 = (row + 1).min(c.get_width()),
+                Key::Enter => return Action::Play(Move::new(c.get_player_turn(), row, column)),
+                Key::Char('u') => {
+                    if self.steps == 0 || c.get_history().len() < self.steps {
+                        self.message = locale::text("play.nothing_undo").to_string();
+                    } else {
+                        return Action::Undo(self.steps);
+                    }
+                }
+                Key::Char('r') => {
+                    if self.steps == 0 || c.get_redo_count() < self.steps {
+                        self.message = locale::text("play.nothing_redo").to_string();
+                    } else {
+                        return Action::Redo(self.steps);
+                    }
+                }
+                Key::Char('?') => match terminal::find_hint(c) {
+                    Some((location, text)) => {
+                        self.cursor = location;
+                        self.message = text;
+                    }
+                    None => self.message = locale::text("play.no_hint").to_string(),
+                },
+                Key::Char('q') | Key::Quit => return Action::Quit,
+                Key::Char(_) => {}
+            }
+        }
+    }
+    fn move_rejected(&mut self, _m: Move, error: MoveError) {
+        self.message = locale::error_text(error).to_string();
+    }
+}